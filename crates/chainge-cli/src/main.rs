@@ -0,0 +1,409 @@
+//! `chainge`: create and inspect receipts from the command line.
+//!
+//! Receipts are read and written as files. Canonical CBOR is the default
+//! on-disk form; the JSON view (SPEC.md Appendix B) is accepted anywhere a
+//! receipt is read, detected by a leading `{` (after whitespace or a BOM).
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use chainge_kernel::{Keypair, Receipt, ReceiptId};
+use clap::{Parser, Subcommand, ValueEnum};
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+#[derive(Parser)]
+#[command(
+    name = "chainge",
+    version,
+    about = "Create and inspect Chainge receipts"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a new keypair and write its seed (hex) to a file.
+    Keygen {
+        /// Where to write the 32-byte seed as hex. Must not exist yet.
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Create and sign a receipt.
+    Create {
+        /// Seed file written by `keygen`.
+        #[arg(long)]
+        key: PathBuf,
+        /// Schema URI.
+        #[arg(long)]
+        schema: String,
+        /// Receipt ID (hex) to reference; repeat for several.
+        #[arg(long = "ref")]
+        refs: Vec<String>,
+        /// Payload as a UTF-8 string.
+        #[arg(long, conflicts_with = "payload_file")]
+        payload: Option<String>,
+        /// Read the payload bytes from a file.
+        #[arg(long)]
+        payload_file: Option<PathBuf>,
+        /// Where to write the receipt.
+        #[arg(long)]
+        out: PathBuf,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Cbor)]
+        format: Format,
+    },
+    /// Turn each line of a JSONL file into a signed receipt.
+    ///
+    /// Each non-blank line must be a JSON value and becomes one receipt's
+    /// payload, byte for byte (only the line ending is dropped). Receipts are written as JSONL in input order.
+    Import {
+        /// Seed file written by `keygen`.
+        #[arg(long)]
+        key: PathBuf,
+        /// Schema URI for every receipt.
+        #[arg(long)]
+        schema: String,
+        /// Make each receipt reference the one before it.
+        #[arg(long)]
+        chain: bool,
+        /// JSONL records to import.
+        input: PathBuf,
+        /// Where to write the receipts (JSONL, one JSON view per line).
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Print a receipt's ID, CID, and JSON view.
+    Show {
+        /// Receipt file (CBOR or JSON).
+        file: PathBuf,
+    },
+    /// Verify one or more receipt files; fails if any is invalid.
+    Verify {
+        /// Receipt files (CBOR or JSON).
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Re-encode a receipt file as CBOR or JSON.
+    Convert {
+        /// Receipt file (CBOR or JSON).
+        file: PathBuf,
+        /// Where to write the converted receipt.
+        #[arg(long)]
+        out: PathBuf,
+        /// Output format.
+        #[arg(long, value_enum)]
+        format: Format,
+    },
+}
+
+/// On-disk receipt encoding.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Canonical DAG-CBOR bytes.
+    Cbor,
+    /// JSON view (SPEC.md Appendix B).
+    Json,
+}
+
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    match cli.command {
+        Command::Keygen { out } => {
+            let keypair = keygen(&out)?;
+            println!("author: {}", keypair.author().to_hex());
+        }
+        Command::Create {
+            key,
+            schema,
+            refs,
+            payload,
+            payload_file,
+            out,
+            format,
+        } => {
+            let keypair = read_keypair(&key)?;
+            let refs = refs
+                .iter()
+                .map(|r| ReceiptId::from_hex(r).map_err(|e| format!("ref {r}: {e}")))
+                .collect::<Result<Vec<_>, _>>()?;
+            let payload = match (payload, payload_file) {
+                (Some(text), _) => text.into_bytes(),
+                (None, Some(path)) => read_file(&path)?,
+                (None, None) => vec![],
+            };
+
+            let receipt =
+                Receipt::new(&keypair, schema, refs, payload).map_err(|e| e.to_string())?;
+            write_receipt(&out, &receipt, format)?;
+            println!("{}", receipt.id().to_hex());
+        }
+        Command::Import {
+            key,
+            schema,
+            chain,
+            input,
+            out,
+        } => {
+            let keypair = read_keypair(&key)?;
+            let text = String::from_utf8(read_file(&input)?)
+                .map_err(|_| format!("{}: not UTF-8", input.display()))?;
+            let receipts = import_records(&keypair, &schema, chain, &text)?;
+
+            let mut jsonl = String::new();
+            for receipt in &receipts {
+                jsonl.push_str(&receipt.to_json());
+                jsonl.push('\n');
+            }
+            write_file(&out, jsonl.as_bytes())?;
+            println!("imported {} records", receipts.len());
+        }
+        Command::Show { file } => {
+            let receipt = read_receipt(&file)?;
+            println!("id:   {}", receipt.id().to_hex());
+            println!("cid:  {}", receipt.cid());
+            println!("json: {}", receipt.to_json());
+        }
+        Command::Verify { files } => {
+            let mut failed = 0;
+            for file in &files {
+                match read_receipt(file) {
+                    Ok(receipt) => println!("ok      {} {}", receipt.id().to_hex(), file.display()),
+                    Err(e) => {
+                        println!("invalid {} ({e})", file.display());
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(format!("{failed} of {} receipts invalid", files.len()));
+            }
+        }
+        Command::Convert { file, out, format } => {
+            let receipt = read_receipt(&file)?;
+            write_receipt(&out, &receipt, format)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, bytes).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Generate a keypair and write its seed to a new file at `path`.
+///
+/// Never overwrites: an existing file may be someone's only copy of a key.
+fn keygen(path: &Path) -> Result<Keypair, String> {
+    let mut seed = Zeroizing::new([0u8; 32]);
+    rand::thread_rng().fill_bytes(seed.as_mut());
+    write_secret(path, Zeroizing::new(hex::encode(*seed)).as_bytes())?;
+    Ok(Keypair::from_seed(&seed))
+}
+
+/// Create `path` (failing if it exists) readable only by its owner.
+fn write_secret(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options
+        .open(path)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    // Don't leave a truncated seed behind; keygen would refuse to replace it
+    file.write_all(bytes)
+        .and_then(|()| file.sync_all())
+        .map_err(|e| {
+            drop(file);
+            let _ = fs::remove_file(path);
+            format!("{}: {e}", path.display())
+        })
+}
+
+/// Read a seed file written by `keygen`.
+///
+/// Every intermediate copy of the seed is zeroized. On Unix, seed files
+/// that other users can read are refused, as `ssh` does for private keys.
+fn read_keypair(path: &Path) -> Result<Keypair, String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(path)
+            .map_err(|e| format!("{}: {e}", path.display()))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(format!(
+                "{}: seed file is accessible by other users (mode {:o}); run chmod 600",
+                path.display(),
+                mode & 0o777
+            ));
+        }
+    }
+    let bytes = Zeroizing::new(read_file(path)?);
+    let text = std::str::from_utf8(&bytes).map_err(|_| "seed file is not hex")?;
+    let mut seed = Zeroizing::new([0u8; 32]);
+    hex::decode_to_slice(text.trim(), seed.as_mut())
+        .map_err(|_| "seed file must contain 32 bytes of hex")?;
+    Ok(Keypair::from_seed(&seed))
+}
+
+/// Decode and verify a receipt from CBOR or JSON bytes.
+///
+/// JSON is recognized by its first non-whitespace byte, after any UTF-8 BOM.
+fn decode_receipt(bytes: &[u8]) -> Result<Receipt, String> {
+    let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let first = text.iter().find(|b| !b.is_ascii_whitespace());
+    let result = if first == Some(&b'{') {
+        let text = std::str::from_utf8(text).map_err(|e| e.to_string())?;
+        Receipt::from_json(text.trim())
+    } else {
+        Receipt::from_bytes(bytes)
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Sign one receipt per non-blank JSONL line; `chain` links each to the last.
+///
+/// `lines()` strips `\n` or `\r\n`; everything else stays in the payload.
+fn import_records(
+    keypair: &Keypair,
+    schema: &str,
+    chain: bool,
+    jsonl: &str,
+) -> Result<Vec<Receipt>, String> {
+    let mut receipts: Vec<Receipt> = Vec::new();
+    for (number, line) in jsonl.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_err = |e: String| format!("line {}: {e}", number + 1);
+        serde_json::from_str::<serde_json::Value>(line).map_err(|e| line_err(e.to_string()))?;
+
+        let refs = match receipts.last() {
+            Some(prev) if chain => vec![prev.id()],
+            _ => vec![],
+        };
+        let receipt = Receipt::new(keypair, schema, refs, line.as_bytes().to_vec())
+            .map_err(|e| line_err(e.to_string()))?;
+        receipts.push(receipt);
+    }
+    Ok(receipts)
+}
+
+fn read_receipt(path: &Path) -> Result<Receipt, String> {
+    decode_receipt(&read_file(path)?).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn write_receipt(path: &Path, receipt: &Receipt, format: Format) -> Result<(), String> {
+    match format {
+        Format::Cbor => write_file(path, &receipt.to_bytes()),
+        Format::Json => write_file(path, format!("{}\n", receipt.to_json()).as_bytes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_detects_format() {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let receipt = Receipt::new(&keypair, "test/v1", vec![], b"hello".to_vec()).unwrap();
+
+        assert_eq!(decode_receipt(&receipt.to_bytes()).unwrap(), receipt);
+
+        let json = format!("{}\n", receipt.to_json());
+        assert_eq!(decode_receipt(json.as_bytes()).unwrap(), receipt);
+
+        // Leading whitespace and a UTF-8 BOM are still JSON
+        let padded = format!("\n  {}", receipt.to_json());
+        assert_eq!(decode_receipt(padded.as_bytes()).unwrap(), receipt);
+        let bom = format!("\u{feff}{}\r\n", receipt.to_json());
+        assert_eq!(decode_receipt(bom.as_bytes()).unwrap(), receipt);
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert!(decode_receipt(b"not a receipt").is_err());
+        assert!(decode_receipt(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_keygen_never_overwrites() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alice.key");
+
+        let keypair = keygen(&path).unwrap();
+        assert!(keygen(&path).is_err());
+        assert_eq!(read_keypair(&path).unwrap().author(), keypair.author());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_keypair_refuses_shared_seed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alice.key");
+        keygen(&path).unwrap();
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(read_keypair(&path).unwrap_err().contains("chmod 600"));
+    }
+
+    #[test]
+    fn test_import_records() {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let jsonl = "{\"site\": 1}\n\n{\"site\": 2}\n[3]\n";
+
+        let receipts = import_records(&keypair, "inspection/v1", true, jsonl).unwrap();
+        assert_eq!(receipts.len(), 3);
+        assert_eq!(receipts[0].payload, b"{\"site\": 1}");
+        assert!(receipts[0].refs.is_empty());
+        assert_eq!(receipts[1].refs, vec![receipts[0].id()]);
+        assert_eq!(receipts[2].refs, vec![receipts[1].id()]);
+
+        let unchained = import_records(&keypair, "inspection/v1", false, jsonl).unwrap();
+        assert!(unchained.iter().all(|r| r.refs.is_empty()));
+
+        let err = import_records(&keypair, "inspection/v1", false, "{}\nnot json\n").unwrap_err();
+        assert!(err.starts_with("line 2:"));
+
+        // Only the line ending is stripped; other whitespace is payload
+        let spaced = import_records(&keypair, "inspection/v1", false, " [1] \r\n  \n").unwrap();
+        assert_eq!(spaced.len(), 1);
+        assert_eq!(spaced[0].payload, b" [1] ");
+    }
+
+    #[test]
+    fn test_cli_parses() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+}
@@ -0,0 +1,781 @@
+//! HTTP/JSON API over a Chainge [`Store`].
+//!
+//! Receipts travel in the JSON view (SPEC.md Appendix B); `POST /receipts`
+//! also accepts canonical CBOR with `Content-Type: application/cbor`.
+//! Clients sign locally: the server holds no keys and only ingests
+//! receipts that verify.
+//!
+//! | Method | Path | Response |
+//! |--------|------|----------|
+//! | `GET`  | `/receipts` | All receipt IDs (hex), sorted |
+//! | `POST` | `/receipts` | Ingest one receipt: `{"id", "inserted"}` |
+//! | `GET`  | `/receipts/{id}` | The receipt |
+//! | `GET`  | `/receipts/{id}/refs_to` | Receipts referencing it, sorted by ID |
+//! | `GET`  | `/authors/{author}/receipts` | The author's receipts |
+//! | `POST` | `/sync` | Receipts the client lacks, given `{"have": [ids], "limit"?}` |
+//!
+//! Sync is the HTTP form of [`chainge_kernel::sync`]: the client pulls what
+//! it lacks from `/sync`, then pushes what the server lacks to `/receipts`.
+//! Each `/sync` response is one page, in topological order: at most `limit`
+//! receipts (capped at [`MAX_SYNC_BATCH`]) and about [`MAX_SYNC_RESPONSE`]
+//! bytes. The client ingests the page, adds its IDs to `have` and asks
+//! again until a response comes back empty.
+//!
+//! A [`TrustPolicy`] limits whose receipts the server accepts and relays.
+//! Receipts by untrusted authors are refused on ingest (`403`) and left
+//! out of every response, including any already in the store. A
+//! [`RateLimit`] caps how fast each author may ingest, and another caps the
+//! body bytes each peer (source IP) may send, or receive from `/sync`; both
+//! answer `429` when exceeded. Peers are only told apart when the router is served with
+//! `into_make_service_with_connect_info::<SocketAddr>()`; otherwise all
+//! requests share one byte budget.
+//!
+//! Bodies larger than [`MAX_RECEIPT_BODY`] (`POST /receipts`) or
+//! [`MAX_SYNC_BODY`] (`POST /sync`) are refused with `413` before they are
+//! decoded.
+
+mod rate;
+
+pub use rate::RateLimit;
+
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Path, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Extension, Json, Router};
+use chainge_kernel::{
+    topological_order, Author, Error, InsertResult, Receipt, ReceiptId, Store, MAX_PAYLOAD_LEN,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use rate::Limiter;
+
+/// Media type for canonical receipt bytes.
+pub const CBOR_CONTENT_TYPE: &str = "application/cbor";
+
+/// Largest `POST /receipts` body accepted: a maximal receipt in JSON, where
+/// hex doubles the payload, plus room for refs, schema and keys.
+pub const MAX_RECEIPT_BODY: usize = 2 * MAX_PAYLOAD_LEN + 16 * 1024;
+
+/// Largest `POST /sync` body accepted (about 30,000 `have` IDs).
+pub const MAX_SYNC_BODY: usize = 2 * 1024 * 1024;
+
+/// Most receipts in one `/sync` response.
+pub const MAX_SYNC_BATCH: usize = 1000;
+
+/// Soft cap on a `/sync` response body, in bytes. A page stops before the
+/// receipt that would cross it, but always holds at least one receipt.
+pub const MAX_SYNC_RESPONSE: usize = 4 * 1024 * 1024;
+
+/// Build the API router over `store` with the default [`Config`]: any
+/// author, no rate limit.
+pub fn router<S: Store + 'static>(store: Arc<S>) -> Router {
+    router_with_config(store, Config::default())
+}
+
+/// Build the API router over `store`, enforcing `config`.
+pub fn router_with_config<S: Store + 'static>(store: Arc<S>, config: Config) -> Router {
+    let state = AppState {
+        store,
+        policy: config.trust,
+        limiter: config.rate_limit.map(Limiter::new),
+        byte_limiter: config.peer_byte_limit.map(Limiter::new),
+    };
+    Router::new()
+        .route(
+            "/receipts",
+            get(list_ids::<S>)
+                .post(ingest::<S>)
+                .layer(DefaultBodyLimit::max(MAX_RECEIPT_BODY)),
+        )
+        .route("/receipts/{id}", get(get_receipt::<S>))
+        .route("/receipts/{id}/refs_to", get(refs_to::<S>))
+        .route("/authors/{author}/receipts", get(by_author::<S>))
+        .route(
+            "/sync",
+            axum::routing::post(sync::<S>).layer(DefaultBodyLimit::max(MAX_SYNC_BODY)),
+        )
+        .with_state(Arc::new(state))
+}
+
+/// Server configuration.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// Whose receipts are accepted and relayed.
+    pub trust: TrustPolicy,
+    /// Per-author ingest limit in receipts; `None` = unlimited.
+    pub rate_limit: Option<RateLimit>,
+    /// Per-peer ingest limit in body bytes; `None` = unlimited.
+    ///
+    /// `burst` should be at least [`MAX_RECEIPT_BODY`], or the largest
+    /// receipts can never be sent.
+    pub peer_byte_limit: Option<RateLimit>,
+}
+
+/// Which authors' receipts the server accepts and relays.
+///
+/// The default trusts everyone. The deny list wins over the allow list.
+#[derive(Debug, Clone, Default)]
+pub struct TrustPolicy {
+    allow: Option<HashSet<Author>>,
+    deny: HashSet<Author>,
+}
+
+impl TrustPolicy {
+    /// Trust only the given authors.
+    pub fn allow_only(authors: impl IntoIterator<Item = Author>) -> Self {
+        Self {
+            allow: Some(authors.into_iter().collect()),
+            deny: HashSet::new(),
+        }
+    }
+
+    /// Additionally distrust `author`.
+    pub fn deny(mut self, author: Author) -> Self {
+        self.deny.insert(author);
+        self
+    }
+
+    /// Whether receipts by `author` are accepted and relayed.
+    pub fn trusts(&self, author: &Author) -> bool {
+        !self.deny.contains(author) && self.allow.as_ref().map_or(true, |a| a.contains(author))
+    }
+
+    fn is_open(&self) -> bool {
+        self.allow.is_none() && self.deny.is_empty()
+    }
+}
+
+struct AppState<S> {
+    store: Arc<S>,
+    policy: TrustPolicy,
+    limiter: Option<Limiter<Author>>,
+    /// Keyed by source IP; `None` when connection info is unavailable.
+    byte_limiter: Option<Limiter<Option<IpAddr>>>,
+}
+
+impl<S: Store> AppState<S> {
+    /// Drop receipts by untrusted authors.
+    fn visible(&self, receipts: Vec<Receipt>) -> Vec<Receipt> {
+        receipts
+            .into_iter()
+            .filter(|r| self.policy.trusts(&r.author))
+            .collect()
+    }
+}
+
+type AppRef<S> = State<Arc<AppState<S>>>;
+
+/// Response body for `POST /receipts`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IngestResponse {
+    /// Receipt ID (hex).
+    pub id: String,
+    /// False if the receipt was already stored.
+    pub inserted: bool,
+}
+
+/// Request body for `POST /sync`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncRequest {
+    /// Receipt IDs (hex) the client already holds.
+    pub have: Vec<String>,
+    /// Most receipts to return; at most [`MAX_SYNC_BATCH`], the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// An error response: `{"error": message}` with a status code.
+#[derive(Debug)]
+pub struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::StorageError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+fn receipts_json(receipts: &[Receipt]) -> Json<Vec<Value>> {
+    Json(receipts.iter().map(Receipt::to_json_value).collect())
+}
+
+async fn list_ids<S: Store>(State(app): AppRef<S>) -> ApiResult<Json<Vec<String>>> {
+    let mut ids = app.store.all_ids()?;
+    if !app.policy.is_open() {
+        let mut trusted = Vec::with_capacity(ids.len());
+        for id in ids {
+            if app
+                .store
+                .get(&id)?
+                .is_some_and(|r| app.policy.trusts(&r.author))
+            {
+                trusted.push(id);
+            }
+        }
+        ids = trusted;
+    }
+    ids.sort();
+    Ok(Json(ids.iter().map(ReceiptId::to_hex).collect()))
+}
+
+async fn ingest<S: Store>(
+    State(app): AppRef<S>,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> ApiResult<(StatusCode, Json<IngestResponse>)> {
+    // Charge every body, valid or not, before spending time decoding it
+    if let Some(limiter) = &app.byte_limiter {
+        if !limiter.try_acquire(&peer_ip(peer), byte_cost(body.len())) {
+            return Err(peer_limited());
+        }
+    }
+
+    let is_cbor = headers
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(CBOR_CONTENT_TYPE.as_bytes()));
+
+    let receipt = if is_cbor {
+        Receipt::from_bytes(&body)?
+    } else {
+        let text = std::str::from_utf8(&body)
+            .map_err(|_| ApiError(StatusCode::BAD_REQUEST, "body is not UTF-8".into()))?;
+        Receipt::from_json(text.trim())?
+    };
+
+    if !app.policy.trusts(&receipt.author) {
+        return Err(ApiError(
+            StatusCode::FORBIDDEN,
+            format!("author not trusted: {}", receipt.author.to_hex()),
+        ));
+    }
+    // Only verified receipts count, so forgeries can't drain another author's bucket
+    if app
+        .limiter
+        .as_ref()
+        .is_some_and(|limiter| !limiter.try_acquire(&receipt.author, 1))
+    {
+        return Err(ApiError(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("rate limit exceeded for author {}", receipt.author.to_hex()),
+        ));
+    }
+
+    let inserted = app.store.insert(&receipt)? == InsertResult::Inserted;
+    let status = if inserted {
+        StatusCode::CREATED
+    } else {
+        StatusCode::OK
+    };
+    let id = receipt.id().to_hex();
+    Ok((status, Json(IngestResponse { id, inserted })))
+}
+
+async fn get_receipt<S: Store>(
+    State(app): AppRef<S>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Value>> {
+    let id = ReceiptId::from_hex(&id)?;
+    match app.store.get(&id)?.filter(|r| app.policy.trusts(&r.author)) {
+        Some(receipt) => Ok(Json(receipt.to_json_value())),
+        None => Err(ApiError(
+            StatusCode::NOT_FOUND,
+            Error::RefNotFound(id).to_string(),
+        )),
+    }
+}
+
+async fn refs_to<S: Store>(
+    State(app): AppRef<S>,
+    Path(id): Path<String>,
+) -> ApiResult<Json<Vec<Value>>> {
+    let id = ReceiptId::from_hex(&id)?;
+    let mut receipts = app.visible(app.store.refs_to(&id)?);
+    receipts.sort_by_cached_key(|r| r.id());
+    Ok(receipts_json(&receipts))
+}
+
+async fn by_author<S: Store>(
+    State(app): AppRef<S>,
+    Path(author): Path<String>,
+) -> ApiResult<Json<Vec<Value>>> {
+    let author = Author::from_hex(&author)?;
+    Ok(receipts_json(&app.visible(app.store.by_author(&author)?)))
+}
+
+/// One page of receipts the client lacks, in topological order so it can
+/// ingest them front to back.
+///
+/// Request and response bytes are both charged to the peer's byte budget.
+/// Sync bodies can exceed `burst`, so the request is admitted whenever the
+/// budget isn't already in debt, and the peer then repays what it used.
+async fn sync<S: Store>(
+    State(app): AppRef<S>,
+    peer: Option<Extension<ConnectInfo<SocketAddr>>>,
+    body: Bytes,
+) -> ApiResult<Response> {
+    let peer = peer_ip(peer);
+    if let Some(limiter) = &app.byte_limiter {
+        if !limiter.try_borrow(&peer, byte_cost(body.len())) {
+            return Err(peer_limited());
+        }
+    }
+
+    let request: SyncRequest = serde_json::from_slice(&body).map_err(|e| {
+        ApiError(
+            StatusCode::BAD_REQUEST,
+            format!("invalid sync request: {e}"),
+        )
+    })?;
+    let limit = request.limit.unwrap_or(MAX_SYNC_BATCH).min(MAX_SYNC_BATCH);
+    let have = request
+        .have
+        .iter()
+        .map(|id| ReceiptId::from_hex(id))
+        .collect::<chainge_kernel::Result<HashSet<_>>>()?;
+
+    let missing: Vec<ReceiptId> = app
+        .store
+        .all_ids()?
+        .into_iter()
+        .filter(|id| !have.contains(id))
+        .collect();
+
+    // Ordering reads only our own store, so a failure (a refs cycle) is
+    // corruption on this side, not a bad request
+    let order = topological_order(app.store.as_ref(), &missing)
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // A prefix of a topological order is still ingestible front to back
+    let mut page = String::from("[");
+    let mut count = 0;
+    for id in order {
+        if count == limit {
+            break;
+        }
+        let Some(receipt) = app.store.get(&id)? else {
+            continue;
+        };
+        if !app.policy.trusts(&receipt.author) {
+            continue;
+        }
+        let json = receipt.to_json();
+        if count > 0 {
+            if page.len() + json.len() + 2 > MAX_SYNC_RESPONSE {
+                break;
+            }
+            page.push(',');
+        }
+        page.push_str(&json);
+        count += 1;
+    }
+    page.push(']');
+
+    if let Some(limiter) = &app.byte_limiter {
+        limiter.charge(&peer, byte_cost(page.len()));
+    }
+    Ok(([(header::CONTENT_TYPE, "application/json")], page).into_response())
+}
+
+/// Source IP of the request, if the server was built with connect info.
+fn peer_ip(peer: Option<Extension<ConnectInfo<SocketAddr>>>) -> Option<IpAddr> {
+    peer.map(|Extension(ConnectInfo(addr))| addr.ip())
+}
+
+fn byte_cost(len: usize) -> u32 {
+    u32::try_from(len).unwrap_or(u32::MAX)
+}
+
+fn peer_limited() -> ApiError {
+    ApiError(
+        StatusCode::TOO_MANY_REQUESTS,
+        "byte limit exceeded for peer".into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use chainge_kernel::{canonical_content, sign_message, Keypair, MemoryStore, MAX_SCHEMA_LEN};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    async fn call(app: &Router, request: Request<Body>) -> (StatusCode, Value) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    fn get_req(uri: &str) -> Request<Body> {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    fn post_req(uri: &str, content_type: &str, body: impl Into<Body>) -> Request<Body> {
+        Request::post(uri)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(body.into())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ingest_and_fetch() {
+        let store = Arc::new(MemoryStore::new());
+        let app = router(store.clone());
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let a = Receipt::new(&keypair, "test/v1", vec![], b"A".to_vec()).unwrap();
+        let b = Receipt::new(&keypair, "test/v1", vec![a.id()], b"B".to_vec()).unwrap();
+
+        let (status, body) =
+            call(&app, post_req("/receipts", "application/json", a.to_json())).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["id"], a.id().to_hex());
+
+        let (status, _) = call(&app, post_req("/receipts", CBOR_CONTENT_TYPE, b.to_bytes())).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        // Re-ingest is idempotent
+        let (status, body) =
+            call(&app, post_req("/receipts", "application/json", a.to_json())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["inserted"], false);
+
+        let (status, body) = call(&app, get_req(&format!("/receipts/{}", a.id().to_hex()))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(Receipt::from_json(&body.to_string()).unwrap(), a);
+
+        let (_, body) = call(
+            &app,
+            get_req(&format!("/receipts/{}/refs_to", a.id().to_hex())),
+        )
+        .await;
+        assert_eq!(body, Value::Array(vec![Receipt::to_json_value(&b)]));
+
+        let uri = format!("/authors/{}/receipts", keypair.author().to_hex());
+        let (_, body) = call(&app, get_req(&uri)).await;
+        assert_eq!(
+            body,
+            Value::Array(vec![Receipt::to_json_value(&a), Receipt::to_json_value(&b)])
+        );
+
+        let (_, body) = call(&app, get_req("/receipts")).await;
+        assert_eq!(body.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let app = router(Arc::new(MemoryStore::new()));
+        let keypair = Keypair::generate();
+        let receipt = Receipt::new(&keypair, "test/v1", vec![], vec![]).unwrap();
+
+        let (status, body) = call(
+            &app,
+            get_req(&format!("/receipts/{}", receipt.id().to_hex())),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["error"].is_string());
+
+        let (status, _) = call(&app, get_req("/receipts/not-hex")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Tampered receipt fails verification
+        let mut bytes = receipt.to_bytes();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        let (status, _) = call(&app, post_req("/receipts", CBOR_CONTENT_TYPE, bytes)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    /// Sign fields directly, skipping the limits `Receipt::new` enforces.
+    fn sign_unchecked(keypair: &Keypair, schema: &str, payload: Vec<u8>) -> Receipt {
+        let author = keypair.author();
+        let content = canonical_content(&author, schema, &[], &payload);
+        Receipt {
+            author,
+            schema: schema.to_string(),
+            refs: vec![],
+            payload,
+            signature: keypair.sign(&sign_message(&content)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_receipts_rejected() {
+        let store = Arc::new(MemoryStore::new());
+        let app = router(store.clone());
+        let keypair = Keypair::generate();
+
+        // Signed and within the body limit, but over the payload limit
+        let big = sign_unchecked(&keypair, "test/v1", vec![0; MAX_PAYLOAD_LEN + 1]);
+        let (status, body) = call(
+            &app,
+            post_req("/receipts", CBOR_CONTENT_TYPE, big.to_bytes()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains("payload too large"));
+
+        let long = sign_unchecked(&keypair, &"x".repeat(MAX_SCHEMA_LEN + 1), vec![]);
+        let (status, body) = call(
+            &app,
+            post_req("/receipts", "application/json", long.to_json()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("schema too long"));
+        assert_eq!(store.count().unwrap(), 0);
+
+        // Bodies past the limit are refused before decoding
+        let request = post_req(
+            "/receipts",
+            CBOR_CONTENT_TYPE,
+            vec![0u8; MAX_RECEIPT_BODY + 1],
+        );
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_trust_policy() {
+        let store = Arc::new(MemoryStore::new());
+        let alice = Keypair::generate();
+        let mallory = Keypair::generate();
+        let config = Config {
+            trust: TrustPolicy::default().deny(mallory.author()),
+            ..Config::default()
+        };
+        let app = router_with_config(store.clone(), config);
+
+        let good = Receipt::new(&alice, "test/v1", vec![], vec![]).unwrap();
+        let spam = Receipt::new(&mallory, "test/v1", vec![good.id()], vec![]).unwrap();
+
+        let (status, _) = call(
+            &app,
+            post_req("/receipts", CBOR_CONTENT_TYPE, good.to_bytes()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = call(
+            &app,
+            post_req("/receipts", CBOR_CONTENT_TYPE, spam.to_bytes()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Already stored (e.g. before the policy changed): never relayed
+        store.insert(&spam).unwrap();
+        let (status, _) = call(&app, get_req(&format!("/receipts/{}", spam.id().to_hex()))).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (_, body) = call(
+            &app,
+            get_req(&format!("/receipts/{}/refs_to", good.id().to_hex())),
+        )
+        .await;
+        assert_eq!(body, Value::Array(vec![]));
+        let (_, body) = call(&app, get_req("/receipts")).await;
+        assert_eq!(body, serde_json::json!([good.id().to_hex()]));
+        let request = serde_json::json!({ "have": [] });
+        let (_, body) = call(
+            &app,
+            post_req("/sync", "application/json", request.to_string()),
+        )
+        .await;
+        assert_eq!(body, Value::Array(vec![Receipt::to_json_value(&good)]));
+
+        let only_alice = TrustPolicy::allow_only([alice.author()]);
+        assert!(only_alice.trusts(&alice.author()));
+        assert!(!only_alice.trusts(&mallory.author()));
+        assert!(!only_alice.deny(alice.author()).trusts(&alice.author()));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let config = Config {
+            rate_limit: Some(RateLimit {
+                per_minute: 1,
+                burst: 2,
+            }),
+            ..Config::default()
+        };
+        let app = router_with_config(Arc::new(MemoryStore::new()), config);
+        let keypair = Keypair::generate();
+
+        let mut statuses = Vec::new();
+        for i in 0u8..3 {
+            let receipt = Receipt::new(&keypair, "test/v1", vec![], vec![i]).unwrap();
+            let request = post_req("/receipts", CBOR_CONTENT_TYPE, receipt.to_bytes());
+            statuses.push(call(&app, request).await.0);
+        }
+        assert_eq!(
+            statuses,
+            [
+                StatusCode::CREATED,
+                StatusCode::CREATED,
+                StatusCode::TOO_MANY_REQUESTS
+            ]
+        );
+
+        // Other authors are unaffected
+        let other = Receipt::new(&Keypair::generate(), "test/v1", vec![], vec![]).unwrap();
+        let request = post_req("/receipts", CBOR_CONTENT_TYPE, other.to_bytes());
+        assert_eq!(call(&app, request).await.0, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_peer_byte_limit() {
+        let config = Config {
+            peer_byte_limit: Some(RateLimit {
+                per_minute: 1,
+                burst: 300,
+            }),
+            ..Config::default()
+        };
+        let app = router_with_config(Arc::new(MemoryStore::new()), config);
+        let from = |ip: [u8; 4], receipt: &Receipt| {
+            let mut request = post_req("/receipts", CBOR_CONTENT_TYPE, receipt.to_bytes());
+            let addr = SocketAddr::from((ip, 4000));
+            request.extensions_mut().insert(ConnectInfo(addr));
+            request
+        };
+
+        // ~180 bytes each: the second overruns the budget, across authors
+        let a = Receipt::new(&Keypair::generate(), "test/v1", vec![], vec![0; 30]).unwrap();
+        let b = Receipt::new(&Keypair::generate(), "test/v1", vec![], vec![1; 30]).unwrap();
+        assert_eq!(
+            call(&app, from([10, 0, 0, 1], &a)).await.0,
+            StatusCode::CREATED
+        );
+        let (status, body) = call(&app, from([10, 0, 0, 1], &b)).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body["error"], "byte limit exceeded for peer");
+
+        // Other peers have their own budget
+        assert_eq!(
+            call(&app, from([10, 0, 0, 2], &b)).await.0,
+            StatusCode::CREATED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_returns_missing_in_order() {
+        let store = Arc::new(MemoryStore::new());
+        let app = router(store.clone());
+        let keypair = Keypair::generate();
+
+        let mut chain: Vec<Receipt> = Vec::new();
+        for i in 0u8..4 {
+            let refs = chain.last().map(|r| vec![r.id()]).unwrap_or_default();
+            let receipt = Receipt::new(&keypair, "test/v1", refs, vec![i]).unwrap();
+            store.insert(&receipt).unwrap();
+            chain.push(receipt);
+        }
+
+        let request = serde_json::json!({ "have": [chain[0].id().to_hex()] });
+        let (status, body) = call(
+            &app,
+            post_req("/sync", "application/json", request.to_string()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let expected: Vec<Value> = chain[1..].iter().map(Receipt::to_json_value).collect();
+        assert_eq!(body, Value::Array(expected));
+    }
+
+    #[tokio::test]
+    async fn test_sync_pages() {
+        let store = Arc::new(MemoryStore::new());
+        let app = router(store.clone());
+        let keypair = Keypair::generate();
+
+        let mut chain: Vec<Receipt> = Vec::new();
+        for i in 0u8..5 {
+            let refs = chain.last().map(|r| vec![r.id()]).unwrap_or_default();
+            let receipt = Receipt::new(&keypair, "test/v1", refs, vec![i]).unwrap();
+            store.insert(&receipt).unwrap();
+            chain.push(receipt);
+        }
+
+        // Pull two at a time, adding each page to `have`, until empty
+        let mut have: Vec<String> = Vec::new();
+        let mut pages = Vec::new();
+        loop {
+            let request = SyncRequest {
+                have: have.clone(),
+                limit: Some(2),
+            };
+            let body = serde_json::to_string(&request).unwrap();
+            let (status, page) = call(&app, post_req("/sync", "application/json", body)).await;
+            assert_eq!(status, StatusCode::OK);
+            let page = page.as_array().unwrap().clone();
+            if page.is_empty() {
+                break;
+            }
+            for receipt in &page {
+                let receipt = Receipt::from_json(&receipt.to_string()).unwrap();
+                have.push(receipt.id().to_hex());
+            }
+            pages.push(page.len());
+        }
+
+        assert_eq!(pages, [2, 2, 1]);
+        let expected: Vec<String> = chain.iter().map(|r| r.id().to_hex()).collect();
+        assert_eq!(have, expected);
+    }
+
+    #[tokio::test]
+    async fn test_sync_charges_peer_bytes() {
+        let config = Config {
+            peer_byte_limit: Some(RateLimit {
+                per_minute: 1,
+                burst: 100,
+            }),
+            ..Config::default()
+        };
+        let store = Arc::new(MemoryStore::new());
+        let app = router_with_config(store.clone(), config);
+        let receipt = Receipt::new(&Keypair::generate(), "test/v1", vec![], vec![0; 64]).unwrap();
+        store.insert(&receipt).unwrap();
+
+        let sync_from = |ip: [u8; 4]| {
+            let mut request = post_req("/sync", "application/json", r#"{"have":[]}"#);
+            let addr = SocketAddr::from((ip, 4000));
+            request.extensions_mut().insert(ConnectInfo(addr));
+            request
+        };
+
+        // The first response outruns the 100-byte budget; the peer is then in debt
+        let (status, body) = call(&app, sync_from([10, 0, 0, 1])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(
+            call(&app, sync_from([10, 0, 0, 1])).await.0,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        assert_eq!(call(&app, sync_from([10, 0, 0, 2])).await.0, StatusCode::OK);
+    }
+}
@@ -0,0 +1,141 @@
+//! `chainge-http`: serve an in-memory receipt store over HTTP.
+//!
+//! Receipts live only as long as the process; this is for development,
+//! integration tests, and as a reference for embedding [`chainge_http::router`].
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use chainge_http::{Config, RateLimit, TrustPolicy, MAX_RECEIPT_BODY};
+use chainge_kernel::{Author, MemoryStore};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(
+    name = "chainge-http",
+    version,
+    about = "Serve a Chainge receipt store over HTTP"
+)]
+struct Cli {
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    addr: SocketAddr,
+    /// Accept only this author (hex); repeat for several.
+    #[arg(long = "allow", value_parser = parse_author)]
+    allow: Vec<Author>,
+    /// Refuse this author (hex); repeat for several.
+    #[arg(long = "deny", value_parser = parse_author)]
+    deny: Vec<Author>,
+    /// Limit each author to this many receipts per minute.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    rate_limit: Option<u32>,
+    /// Receipts an author may send at once under --rate-limit.
+    #[arg(
+        long,
+        default_value_t = 10,
+        requires = "rate_limit",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    burst: u32,
+    /// Limit each peer (source IP) to this many body bytes per minute.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    peer_byte_limit: Option<u32>,
+}
+
+fn parse_author(s: &str) -> Result<Author, String> {
+    Author::from_hex(s).map_err(|e| e.to_string())
+}
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let cli = Cli::parse();
+    let mut policy = if cli.allow.is_empty() {
+        TrustPolicy::default()
+    } else {
+        TrustPolicy::allow_only(cli.allow)
+    };
+    for author in cli.deny {
+        policy = policy.deny(author);
+    }
+    let config = Config {
+        trust: policy,
+        rate_limit: cli.rate_limit.map(|per_minute| RateLimit {
+            per_minute,
+            burst: cli.burst,
+        }),
+        // Always leave room for one maximal receipt
+        peer_byte_limit: cli.peer_byte_limit.map(|per_minute| RateLimit {
+            per_minute,
+            burst: per_minute.max(MAX_RECEIPT_BODY as u32),
+        }),
+    };
+    let app = chainge_http::router_with_config(Arc::new(MemoryStore::new()), config);
+
+    let listener = tokio::net::TcpListener::bind(cli.addr).await?;
+    eprintln!("listening on {}", listener.local_addr()?);
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+}
+
+/// Resolve on Ctrl-C (or SIGTERM on Unix). In-flight requests are allowed
+/// to finish before `serve` returns.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        // If the handler can't be installed, wait on SIGTERM alone
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => std::future::pending().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+    eprintln!("shutting down");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_parses() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_zero_limits_rejected() {
+        let parse = |args: &[&str]| Cli::try_parse_from([&["chainge-http"], args].concat());
+
+        assert!(parse(&["--rate-limit", "0"]).is_err());
+        assert!(parse(&["--rate-limit", "5", "--burst", "0"]).is_err());
+        assert!(parse(&["--peer-byte-limit", "0"]).is_err());
+        assert!(parse(&[
+            "--rate-limit",
+            "5",
+            "--burst",
+            "1",
+            "--peer-byte-limit",
+            "1"
+        ])
+        .is_ok());
+    }
+}
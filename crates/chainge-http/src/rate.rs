@@ -0,0 +1,240 @@
+//! Token buckets for ingest rate limiting.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Token-bucket limit: up to `burst` units at once, refilling at
+/// `per_minute`.
+///
+/// Units are receipts for [`Config::rate_limit`](crate::Config::rate_limit)
+/// and body bytes for [`Config::peer_byte_limit`](crate::Config::peer_byte_limit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Sustained units per minute.
+    pub per_minute: u32,
+    /// Bucket capacity.
+    pub burst: u32,
+}
+
+/// Most buckets a limiter keeps. Keys are cheap to mint (a fresh author
+/// key, a new source address), so the map is bounded. When it is full,
+/// buckets that have refilled are dropped; failing that, the one used
+/// least recently is. A flood of new keys can reset an idle key's bucket
+/// but never locks out a new key.
+pub(crate) const MAX_BUCKETS: usize = 10_000;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+/// Buckets for every key seen recently.
+#[derive(Debug)]
+pub(crate) struct Limiter<K> {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<K, Bucket>>,
+}
+
+impl<K: Hash + Eq + Copy> Limiter<K> {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take `cost` tokens for `key`; false if the bucket holds fewer.
+    pub(crate) fn try_acquire(&self, key: &K, cost: u32) -> bool {
+        self.try_acquire_at(key, cost, Instant::now())
+    }
+
+    /// Take `cost` tokens for `key` as long as the bucket isn't already in
+    /// debt; false if it is. For requests whose size may exceed `burst`.
+    pub(crate) fn try_borrow(&self, key: &K, cost: u32) -> bool {
+        self.try_borrow_at(key, cost, Instant::now())
+    }
+
+    /// Take `cost` tokens for `key` unconditionally, going into debt if
+    /// needed. For work already done, such as a response just built.
+    pub(crate) fn charge(&self, key: &K, cost: u32) {
+        self.with_tokens(key, Instant::now(), |tokens| *tokens -= f64::from(cost));
+    }
+
+    fn try_acquire_at(&self, key: &K, cost: u32, now: Instant) -> bool {
+        let cost = f64::from(cost);
+        self.with_tokens(key, now, |tokens| {
+            if *tokens >= cost {
+                *tokens -= cost;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    fn try_borrow_at(&self, key: &K, cost: u32, now: Instant) -> bool {
+        self.with_tokens(key, now, |tokens| {
+            if *tokens > 0.0 {
+                *tokens -= f64::from(cost);
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Refill `key`'s bucket (creating it if needed) and apply `f` to it.
+    fn with_tokens<R>(&self, key: &K, now: Instant, f: impl FnOnce(&mut f64) -> R) -> R {
+        let capacity = f64::from(self.limit.burst);
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if !buckets.contains_key(key) && buckets.len() >= MAX_BUCKETS {
+            // A full bucket behaves exactly like a fresh one, so it can go
+            buckets.retain(|_, bucket| self.refilled(bucket, now) < capacity);
+            if buckets.len() >= MAX_BUCKETS {
+                let stalest = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.refilled)
+                    .map(|(key, _)| *key);
+                if let Some(key) = stalest {
+                    buckets.remove(&key);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(*key).or_insert(Bucket {
+            tokens: capacity,
+            refilled: now,
+        });
+        bucket.tokens = self.refilled(bucket, now).min(capacity);
+        bucket.refilled = now;
+        f(&mut bucket.tokens)
+    }
+
+    /// Tokens `bucket` would hold at `now`, before capping.
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens + elapsed * f64::from(self.limit.per_minute) / 60.0
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.buckets.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chainge_kernel::Keypair;
+    use std::time::Duration;
+
+    #[test]
+    fn test_bucket_refills() {
+        let limiter = Limiter::new(RateLimit {
+            per_minute: 60,
+            burst: 2,
+        });
+        let alice = Keypair::generate().author();
+        let bob = Keypair::generate().author();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_at(&alice, 1, start));
+        assert!(limiter.try_acquire_at(&alice, 1, start));
+        assert!(!limiter.try_acquire_at(&alice, 1, start));
+
+        // Buckets are per author
+        assert!(limiter.try_acquire_at(&bob, 1, start));
+
+        // One token per second at 60/minute
+        assert!(limiter.try_acquire_at(&alice, 1, start + Duration::from_secs(1)));
+        assert!(!limiter.try_acquire_at(&alice, 1, start + Duration::from_secs(1)));
+
+        // Refill is capped at the burst size
+        let later = start + Duration::from_secs(3600);
+        assert!(limiter.try_acquire_at(&alice, 1, later));
+        assert!(limiter.try_acquire_at(&alice, 1, later));
+        assert!(!limiter.try_acquire_at(&alice, 1, later));
+    }
+
+    #[test]
+    fn test_cost() {
+        let limiter = Limiter::new(RateLimit {
+            per_minute: 600,
+            burst: 1000,
+        });
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_at(&(), 700, start));
+        assert!(!limiter.try_acquire_at(&(), 400, start));
+        assert!(limiter.try_acquire_at(&(), 300, start));
+
+        // 10 per second
+        assert!(!limiter.try_acquire_at(&(), 50, start + Duration::from_secs(4)));
+        assert!(limiter.try_acquire_at(&(), 50, start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_borrow_goes_into_debt() {
+        let limiter = Limiter::new(RateLimit {
+            per_minute: 60,
+            burst: 10,
+        });
+        let start = Instant::now();
+
+        // Larger than the burst, but the bucket isn't in debt yet
+        assert!(limiter.try_borrow_at(&(), 25, start));
+        assert!(!limiter.try_borrow_at(&(), 1, start));
+
+        // 15 tokens of debt take 15 seconds to repay
+        let later = start + Duration::from_secs(15);
+        assert!(!limiter.try_borrow_at(&(), 1, later));
+        assert!(limiter.try_borrow_at(&(), 1, later + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_buckets_are_bounded() {
+        let limiter = Limiter::new(RateLimit {
+            per_minute: 60,
+            burst: 2,
+        });
+        let start = Instant::now();
+
+        for key in 0..MAX_BUCKETS {
+            assert!(limiter.try_acquire_at(&key, 1, start));
+        }
+        assert_eq!(limiter.len(), MAX_BUCKETS);
+
+        // Once the others have refilled they are dropped to make room
+        let later = start + Duration::from_secs(2);
+        assert!(limiter.try_acquire_at(&MAX_BUCKETS, 1, later));
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn test_flood_does_not_lock_out_new_keys() {
+        let limiter = Limiter::new(RateLimit {
+            per_minute: 1,
+            burst: 2,
+        });
+        let start = Instant::now();
+
+        // A flood of throwaway keys, each with a partly used bucket
+        for key in 0..MAX_BUCKETS + 100 {
+            let now = start + Duration::from_millis(key as u64);
+            assert!(limiter.try_acquire_at(&key, 1, now));
+        }
+        assert_eq!(limiter.len(), MAX_BUCKETS);
+
+        // A real newcomer still gets its full burst, and the map stays bounded
+        let now = start + Duration::from_secs(30);
+        let newcomer = usize::MAX;
+        assert!(limiter.try_acquire_at(&newcomer, 1, now));
+        assert!(limiter.try_acquire_at(&newcomer, 1, now));
+        assert!(!limiter.try_acquire_at(&newcomer, 1, now));
+        assert_eq!(limiter.len(), MAX_BUCKETS);
+    }
+}
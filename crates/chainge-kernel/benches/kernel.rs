@@ -0,0 +1,155 @@
+//! Benchmarks for canonicalization, verification, and store operations.
+//!
+//! Track regressions against a saved baseline:
+//!
+//! ```bash
+//! cargo bench -p chainge-kernel -- --save-baseline main
+//! cargo bench -p chainge-kernel -- --baseline main
+//! ```
+
+use chainge_kernel::{
+    canonical_content, sync, walk_refs, Direction, Keypair, MemoryStore, Receipt, ReceiptId, Store,
+    MAX_PAYLOAD_LEN, MAX_REFS,
+};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+/// Payload sizes: empty, typical JSON-sized, and the 64 KB maximum.
+const PAYLOAD_SIZES: [usize; 3] = [0, 1024, MAX_PAYLOAD_LEN];
+
+fn sample_receipt(payload_len: usize) -> Receipt {
+    let keypair = Keypair::from_seed(&[0x42; 32]);
+    let refs = (0..4).map(|i| ReceiptId::from_bytes([i; 32])).collect();
+    Receipt::new(&keypair, "bench/v1", refs, vec![0xab; payload_len]).unwrap()
+}
+
+/// A chain of `n` receipts, each referencing the previous one.
+fn chain(n: usize) -> Vec<Receipt> {
+    let keypair = Keypair::from_seed(&[0x42; 32]);
+    let mut receipts: Vec<Receipt> = Vec::with_capacity(n);
+    for i in 0..n {
+        let refs = receipts.last().map(|r| vec![r.id()]).unwrap_or_default();
+        let payload = (i as u64).to_be_bytes().to_vec();
+        receipts.push(Receipt::new(&keypair, "bench/v1", refs, payload).unwrap());
+    }
+    receipts
+}
+
+fn bench_canonical(c: &mut Criterion) {
+    let mut group = c.benchmark_group("canonical");
+    for size in PAYLOAD_SIZES {
+        let receipt = sample_receipt(size);
+
+        group.bench_with_input(BenchmarkId::new("content", size), &receipt, |b, r| {
+            b.iter(|| canonical_content(&r.author, &r.schema, &r.refs, black_box(&r.payload)))
+        });
+        group.bench_with_input(BenchmarkId::new("to_bytes", size), &receipt, |b, r| {
+            b.iter(|| black_box(r).to_bytes())
+        });
+        group.bench_with_input(BenchmarkId::new("to_bytes_into", size), &receipt, |b, r| {
+            let mut buf = Vec::new();
+            b.iter(|| {
+                buf.clear();
+                black_box(r).to_bytes_into(&mut buf);
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("id", size), &receipt, |b, r| {
+            b.iter(|| black_box(r).id())
+        });
+
+        let bytes = receipt.to_bytes();
+        group.bench_with_input(BenchmarkId::new("from_bytes", size), &bytes, |b, bytes| {
+            b.iter(|| Receipt::from_bytes(black_box(bytes)).unwrap())
+        });
+    }
+
+    // Refs dominate encoding cost for small payloads
+    let keypair = Keypair::from_seed(&[0x42; 32]);
+    let refs = (0..MAX_REFS)
+        .map(|i| ReceiptId::from_bytes([i as u8; 32]))
+        .collect();
+    let receipt = Receipt::new(&keypair, "bench/v1", refs, vec![]).unwrap();
+    group.bench_function("to_bytes/max_refs", |b| {
+        b.iter(|| black_box(&receipt).to_bytes())
+    });
+    group.finish();
+}
+
+fn bench_receipt(c: &mut Criterion) {
+    let mut group = c.benchmark_group("receipt");
+    let keypair = Keypair::from_seed(&[0x42; 32]);
+
+    for size in PAYLOAD_SIZES {
+        let payload = vec![0xab; size];
+        group.bench_with_input(BenchmarkId::new("new", size), &payload, |b, payload| {
+            b.iter(|| Receipt::new(&keypair, "bench/v1", vec![], payload.clone()).unwrap())
+        });
+
+        let receipt = sample_receipt(size);
+        group.bench_with_input(BenchmarkId::new("verify", size), &receipt, |b, r| {
+            b.iter(|| black_box(r).verify().unwrap())
+        });
+    }
+
+    let receipt = sample_receipt(1024);
+    let json = receipt.to_json();
+    group.bench_function("to_json/1024", |b| b.iter(|| black_box(&receipt).to_json()));
+    group.bench_function("from_json/1024", |b| {
+        b.iter(|| Receipt::from_json(black_box(&json)).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_store(c: &mut Criterion) {
+    let mut group = c.benchmark_group("store");
+    let receipts = chain(1000);
+
+    group.bench_function("insert/1000", |b| {
+        b.iter_batched(
+            MemoryStore::new,
+            |store| {
+                for r in &receipts {
+                    store.insert(r).unwrap();
+                }
+                store
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let store = MemoryStore::new();
+    for r in &receipts {
+        store.insert(r).unwrap();
+    }
+    let ids: Vec<ReceiptId> = receipts.iter().map(|r| r.id()).collect();
+
+    group.bench_function("get/1000", |b| {
+        b.iter(|| {
+            for id in &ids {
+                black_box(store.get(id).unwrap());
+            }
+        })
+    });
+    group.bench_function("ancestors/1000", |b| {
+        b.iter(|| walk_refs(&store, &ids[999], Direction::Ancestors, None).unwrap())
+    });
+
+    group.bench_function("sync/1000", |b| {
+        b.iter_batched(
+            || {
+                // Each side holds half the chain
+                let (left, right) = (MemoryStore::new(), MemoryStore::new());
+                for (i, r) in receipts.iter().enumerate() {
+                    let side = if i % 2 == 0 { &left } else { &right };
+                    side.insert(r).unwrap();
+                }
+                (left, right)
+            },
+            |(left, right)| sync(&left, &right).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_canonical, bench_receipt, bench_store);
+criterion_main!(benches);
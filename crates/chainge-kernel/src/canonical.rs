@@ -8,6 +8,7 @@
 //!
 //! **CRITICAL**: This encoding is FROZEN. Changes break all existing signatures.
 
+#[cfg(test)]
 use ciborium::value::Value;
 
 use crate::crypto::{Author, Signature};
@@ -21,7 +22,7 @@ pub const SIGN_DOMAIN: &[u8] = b"chainge/receipt-sig/v1";
 pub const ID_DOMAIN: &[u8] = b"chainge/receipt-id/v1";
 
 /// CBOR map key names.
-mod keys {
+pub(crate) mod keys {
     pub const AUTHOR: &str = "author";
     pub const PAYLOAD: &str = "payload";
     pub const REFS: &str = "refs";
@@ -40,28 +41,23 @@ pub fn canonical_content(
     refs: &[ReceiptId],
     payload: &[u8],
 ) -> Vec<u8> {
-    // Build entries - refs is always present
-    let entries = vec![
-        (
-            Value::Text(keys::REFS.to_string()),
-            Value::Array(refs.iter().map(|r| Value::Bytes(r.0.to_vec())).collect()),
-        ),
-        (
-            Value::Text(keys::AUTHOR.to_string()),
-            Value::Bytes(author.0.to_vec()),
-        ),
-        (
-            Value::Text(keys::SCHEMA.to_string()),
-            Value::Text(schema.to_string()),
-        ),
-        (
-            Value::Text(keys::PAYLOAD.to_string()),
-            Value::Bytes(payload.to_vec()),
-        ),
-    ];
-
-    let value = Value::Map(entries);
-    encode_cbor_canonical(&value)
+    let mut buf = Vec::with_capacity(encoded_len(schema, refs, payload, None));
+    canonical_content_into(&mut buf, author, schema, refs, payload);
+    buf
+}
+
+/// Append canonical content bytes to `buf`.
+///
+/// Same bytes as [`canonical_content`], written without intermediate
+/// allocations so callers can reuse one buffer across many receipts.
+pub fn canonical_content_into(
+    buf: &mut Vec<u8>,
+    author: &Author,
+    schema: &str,
+    refs: &[ReceiptId],
+    payload: &[u8],
+) {
+    encode_fields(buf, author, schema, refs, payload, None);
 }
 
 /// Build the message to sign (with domain separation).
@@ -82,116 +78,272 @@ pub fn canonical_receipt(
     payload: &[u8],
     signature: &Signature,
 ) -> Vec<u8> {
-    // Build entries - all 5 fields, sorted by CBOR key encoding
-    let entries = vec![
-        (
-            Value::Text(keys::REFS.to_string()),
-            Value::Array(refs.iter().map(|r| Value::Bytes(r.0.to_vec())).collect()),
-        ),
-        (
-            Value::Text(keys::AUTHOR.to_string()),
-            Value::Bytes(author.0.to_vec()),
-        ),
-        (
-            Value::Text(keys::SCHEMA.to_string()),
-            Value::Text(schema.to_string()),
-        ),
-        (
-            Value::Text(keys::PAYLOAD.to_string()),
-            Value::Bytes(payload.to_vec()),
-        ),
-        (
-            Value::Text(keys::SIGNATURE.to_string()),
-            Value::Bytes(signature.0.to_vec()),
-        ),
-    ];
-
-    let value = Value::Map(entries);
-    encode_cbor_canonical(&value)
+    let mut buf = Vec::with_capacity(encoded_len(schema, refs, payload, Some(signature)));
+    canonical_receipt_into(&mut buf, author, schema, refs, payload, signature);
+    buf
 }
 
-/// Decode receipt from canonical CBOR bytes.
-pub fn decode_receipt(
-    bytes: &[u8],
-) -> Result<(Author, String, Vec<ReceiptId>, Vec<u8>, Signature)> {
-    // Parse CBOR
-    let cursor = std::io::Cursor::new(bytes);
-    let value: Value =
-        ciborium::from_reader(cursor).map_err(|e| Error::DecodingError(e.to_string()))?;
-
-    let map = match &value {
-        Value::Map(m) => m,
-        _ => return Err(Error::MalformedReceipt("expected map".into())),
-    };
+/// Append canonical receipt bytes to `buf`.
+///
+/// Same bytes as [`canonical_receipt`], written without intermediate
+/// allocations so callers can reuse one buffer across many receipts.
+pub fn canonical_receipt_into(
+    buf: &mut Vec<u8>,
+    author: &Author,
+    schema: &str,
+    refs: &[ReceiptId],
+    payload: &[u8],
+    signature: &Signature,
+) {
+    encode_fields(buf, author, schema, refs, payload, Some(signature));
+}
 
-    // Helper to get value by string key
-    let get = |key: &str| -> Option<&Value> {
-        map.iter()
-            .find(|(k, _)| matches!(k, Value::Text(s) if s == key))
-            .map(|(_, v)| v)
-    };
+/// Write the receipt map directly, in canonical key order.
+///
+/// Text keys sort by encoded bytes, i.e. length first: refs, author,
+/// schema, payload, signature. `test_direct_matches_generic` checks this
+/// against the generic RFC 8949 encoder.
+fn encode_fields(
+    buf: &mut Vec<u8>,
+    author: &Author,
+    schema: &str,
+    refs: &[ReceiptId],
+    payload: &[u8],
+    signature: Option<&Signature>,
+) {
+    encode_uint(buf, 5, if signature.is_some() { 5 } else { 4 });
+
+    encode_text(buf, keys::REFS);
+    encode_uint(buf, 4, refs.len() as u64);
+    for r in refs {
+        encode_bytes(buf, &r.0);
+    }
+    encode_text(buf, keys::AUTHOR);
+    encode_bytes(buf, &author.0);
+    encode_text(buf, keys::SCHEMA);
+    encode_text(buf, schema);
+    encode_text(buf, keys::PAYLOAD);
+    encode_bytes(buf, payload);
+    if let Some(signature) = signature {
+        encode_text(buf, keys::SIGNATURE);
+        encode_bytes(buf, &signature.0);
+    }
+}
 
-    // Parse author
-    let author = match get(keys::AUTHOR) {
-        Some(Value::Bytes(b)) if b.len() == 32 => {
-            let mut arr = [0u8; 32];
-            arr.copy_from_slice(b);
-            Author::from_bytes(arr)
-        }
-        _ => return Err(Error::MalformedReceipt("invalid author".into())),
+/// Exact encoded size, so buffers are allocated once.
+pub(crate) fn encoded_len(
+    schema: &str,
+    refs: &[ReceiptId],
+    payload: &[u8],
+    signature: Option<&Signature>,
+) -> usize {
+    // CBOR head size for a length (see encode_uint)
+    let head = |n: usize| match n as u64 {
+        0..=23 => 1,
+        24..=0xff => 2,
+        0x100..=0xffff => 3,
+        0x1_0000..=0xffff_ffff => 5,
+        _ => 9,
+    };
+    let keys = 4 + 1 + 6 + 1 + 6 + 1 + 7 + 1;
+    let refs_len = head(refs.len()) + refs.len() * (head(32) + 32);
+    let fields = refs_len
+        + (head(32) + 32)
+        + (head(schema.len()) + schema.len())
+        + (head(payload.len()) + payload.len());
+    let signature_len = match signature {
+        Some(_) => 1 + 9 + head(64) + 64,
+        None => 0,
     };
+    1 + keys + fields + signature_len
+}
+
+/// Receipt fields as decoded from CBOR: (author, schema, refs, payload, signature).
+pub type DecodedReceipt = (Author, String, Vec<ReceiptId>, Vec<u8>, Signature);
 
-    // Parse schema
-    let schema = match get(keys::SCHEMA) {
-        Some(Value::Text(s)) => s.clone(),
-        _ => return Err(Error::MalformedReceipt("invalid schema".into())),
+/// Decode receipt from canonical CBOR bytes.
+///
+/// Strict: the input must be exactly the bytes [`canonical_receipt`] would
+/// produce for the decoded fields. Rejects indefinite lengths, non-minimal
+/// integer/length encodings, unsorted or duplicate map keys, unknown keys,
+/// and trailing bytes, so a decoded receipt always re-encodes to its input.
+pub fn decode_receipt(bytes: &[u8]) -> Result<DecodedReceipt> {
+    let mut reader = Reader::new(bytes);
+
+    let len = match reader.head()? {
+        (MAJOR_MAP, len) => len,
+        _ => return Err(Error::MalformedReceipt("expected map".into())),
     };
 
-    // Parse refs (must be present, may be empty)
-    let refs = match get(keys::REFS) {
-        Some(Value::Array(arr)) => {
-            let mut refs = Vec::with_capacity(arr.len());
-            for item in arr {
-                match item {
-                    Value::Bytes(b) if b.len() == 32 => {
-                        let mut arr = [0u8; 32];
-                        arr.copy_from_slice(b);
-                        refs.push(ReceiptId::from_bytes(arr));
-                    }
-                    _ => return Err(Error::MalformedReceipt("invalid ref".into())),
+    let mut author = None;
+    let mut schema = None;
+    let mut refs = None;
+    let mut payload = None;
+    let mut signature = None;
+
+    let mut prev_key: Option<&[u8]> = None;
+    for _ in 0..len {
+        // Keys must be strictly increasing by encoded bytes (sorted, no duplicates)
+        let key_start = reader.pos;
+        let key = match reader.head()? {
+            (MAJOR_TEXT, len) => reader.text(len)?,
+            _ => return Err(Error::MalformedReceipt("map key must be text".into())),
+        };
+        let key_bytes = &bytes[key_start..reader.pos];
+        if let Some(prev) = prev_key {
+            match prev.cmp(key_bytes) {
+                std::cmp::Ordering::Less => {}
+                std::cmp::Ordering::Equal => {
+                    return Err(Error::NonCanonical(format!("duplicate map key: {key}")))
+                }
+                std::cmp::Ordering::Greater => {
+                    return Err(Error::NonCanonical(format!("map key out of order: {key}")))
                 }
             }
-            refs
         }
-        _ => return Err(Error::MalformedReceipt("missing or invalid refs".into())),
-    };
+        prev_key = Some(key_bytes);
 
-    // Parse payload
-    let payload = match get(keys::PAYLOAD) {
-        Some(Value::Bytes(b)) => b.clone(),
-        _ => return Err(Error::MalformedReceipt("invalid payload".into())),
-    };
+        match key {
+            keys::AUTHOR => {
+                author = Some(Author::from_bytes(reader.fixed_bytes("author")?));
+            }
+            keys::SCHEMA => {
+                schema = match reader.head()? {
+                    (MAJOR_TEXT, len) => Some(reader.text(len)?.to_string()),
+                    _ => return Err(invalid("schema")),
+                };
+            }
+            keys::REFS => {
+                let count = match reader.head()? {
+                    (MAJOR_ARRAY, count) => count,
+                    _ => return Err(Error::MalformedReceipt("missing or invalid refs".into())),
+                };
+                // Each ref is at least 33 bytes; don't trust count for allocation
+                let mut items = Vec::with_capacity((count as usize).min(reader.remaining() / 33));
+                for _ in 0..count {
+                    items.push(ReceiptId::from_bytes(reader.fixed_bytes("ref")?));
+                }
+                refs = Some(items);
+            }
+            keys::PAYLOAD => {
+                payload = match reader.head()? {
+                    (MAJOR_BYTES, len) => Some(reader.take(len)?.to_vec()),
+                    _ => return Err(invalid("payload")),
+                };
+            }
+            keys::SIGNATURE => {
+                signature = Some(Signature::from_bytes(reader.fixed_bytes("signature")?));
+            }
+            other => return Err(Error::MalformedReceipt(format!("unknown key: {other}"))),
+        }
+    }
 
-    // Parse signature
-    let signature = match get(keys::SIGNATURE) {
-        Some(Value::Bytes(b)) if b.len() == 64 => {
-            let mut arr = [0u8; 64];
-            arr.copy_from_slice(b);
-            Signature::from_bytes(arr)
+    if reader.remaining() != 0 {
+        return Err(Error::NonCanonical("trailing bytes after receipt".into()));
+    }
+
+    Ok((
+        author.ok_or_else(|| invalid("author"))?,
+        schema.ok_or_else(|| invalid("schema"))?,
+        refs.ok_or_else(|| Error::MalformedReceipt("missing or invalid refs".into()))?,
+        payload.ok_or_else(|| invalid("payload"))?,
+        signature.ok_or_else(|| invalid("signature"))?,
+    ))
+}
+
+fn invalid(field: &str) -> Error {
+    Error::MalformedReceipt(format!("invalid {field}"))
+}
+
+/// CBOR major types used by receipts.
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+
+/// Strict reader over canonical CBOR.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: u64) -> Result<&'a [u8]> {
+        if len > self.remaining() as u64 {
+            return Err(Error::DecodingError("unexpected end of input".into()));
         }
-        _ => return Err(Error::MalformedReceipt("invalid signature".into())),
-    };
+        let start = self.pos;
+        self.pos += len as usize;
+        Ok(&self.bytes[start..self.pos])
+    }
+
+    /// Read an item head: (major type, argument).
+    ///
+    /// Rejects indefinite lengths and arguments not in their smallest encoding.
+    fn head(&mut self) -> Result<(u8, u64)> {
+        let initial = self.take(1)?[0];
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        let (arg, min) = match info {
+            0..=23 => return Ok((major, info as u64)),
+            24 => (self.take(1)?[0] as u64, 24),
+            25 => (
+                u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64,
+                0x100,
+            ),
+            26 => (
+                u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64,
+                0x1_0000,
+            ),
+            27 => (
+                u64::from_be_bytes(self.take(8)?.try_into().unwrap()),
+                0x1_0000_0000,
+            ),
+            31 => return Err(Error::NonCanonical("indefinite length".into())),
+            _ => {
+                return Err(Error::DecodingError(format!(
+                    "reserved additional info {info}"
+                )))
+            }
+        };
 
-    Ok((author, schema, refs, payload, signature))
+        if arg < min {
+            return Err(Error::NonCanonical("non-minimal integer encoding".into()));
+        }
+        Ok((major, arg))
+    }
+
+    fn text(&mut self, len: u64) -> Result<&'a str> {
+        std::str::from_utf8(self.take(len)?).map_err(|e| Error::DecodingError(e.to_string()))
+    }
+
+    /// Read a byte string of exactly `N` bytes.
+    fn fixed_bytes<const N: usize>(&mut self, field: &str) -> Result<[u8; N]> {
+        match self.head()? {
+            (MAJOR_BYTES, len) if len == N as u64 => Ok(self.take(len)?.try_into().unwrap()),
+            _ => Err(invalid(field)),
+        }
+    }
 }
 
-/// Encode a CBOR value to canonical bytes.
+#[cfg(test)]
+/// Encode a CBOR value to canonical bytes (generic RFC 8949 encoder).
 fn encode_cbor_canonical(value: &Value) -> Vec<u8> {
     let mut buf = Vec::new();
     encode_value(&mut buf, value);
     buf
 }
 
+#[cfg(test)]
 /// Recursively encode a CBOR value.
 fn encode_value(buf: &mut Vec<u8>, value: &Value) {
     match value {
@@ -206,6 +358,7 @@ fn encode_value(buf: &mut Vec<u8>, value: &Value) {
     }
 }
 
+#[cfg(test)]
 fn encode_integer(buf: &mut Vec<u8>, i: ciborium::value::Integer) {
     let n: i128 = i.into();
     if n >= 0 {
@@ -244,6 +397,7 @@ fn encode_text(buf: &mut Vec<u8>, s: &str) {
     buf.extend_from_slice(s.as_bytes());
 }
 
+#[cfg(test)]
 fn encode_array(buf: &mut Vec<u8>, arr: &[Value]) {
     encode_uint(buf, 4, arr.len() as u64);
     for item in arr {
@@ -251,6 +405,7 @@ fn encode_array(buf: &mut Vec<u8>, arr: &[Value]) {
     }
 }
 
+#[cfg(test)]
 fn encode_map(buf: &mut Vec<u8>, entries: &[(Value, Value)]) {
     // RFC 8949 canonical: sort keys by CBOR-encoded bytes
     let mut sorted: Vec<_> = entries
@@ -276,6 +431,95 @@ mod tests {
     use super::*;
     use crate::crypto::Keypair;
 
+    /// The generic encoder: builds a Value tree and sorts map keys.
+    fn generic_receipt(
+        author: &Author,
+        schema: &str,
+        refs: &[ReceiptId],
+        payload: &[u8],
+        signature: Option<&Signature>,
+    ) -> Vec<u8> {
+        let mut entries = vec![
+            (
+                Value::Text(keys::PAYLOAD.into()),
+                Value::Bytes(payload.to_vec()),
+            ),
+            (Value::Text(keys::SCHEMA.into()), Value::Text(schema.into())),
+            (
+                Value::Text(keys::AUTHOR.into()),
+                Value::Bytes(author.0.to_vec()),
+            ),
+            (
+                Value::Text(keys::REFS.into()),
+                Value::Array(refs.iter().map(|r| Value::Bytes(r.0.to_vec())).collect()),
+            ),
+        ];
+        if let Some(signature) = signature {
+            entries.push((
+                Value::Text(keys::SIGNATURE.into()),
+                Value::Bytes(signature.0.to_vec()),
+            ));
+        }
+        encode_cbor_canonical(&Value::Map(entries))
+    }
+
+    #[test]
+    fn test_direct_matches_generic() {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let author = keypair.author();
+        let signature = keypair.sign(b"anything");
+
+        // Cover every head size for schema, refs, and payload lengths
+        let schemas = ["", "test/v1", &"s".repeat(24), &"s".repeat(256)];
+        let ref_counts = [0usize, 1, 23, 24, 128];
+        let payload_lens = [0usize, 23, 24, 255, 256, 65535, 65536];
+
+        for schema in schemas {
+            for &count in &ref_counts {
+                let refs: Vec<ReceiptId> = (0..count)
+                    .map(|i| ReceiptId::from_bytes([i as u8; 32]))
+                    .collect();
+                for &len in &payload_lens {
+                    let payload = vec![0xab; len];
+
+                    let content = canonical_content(&author, schema, &refs, &payload);
+                    assert_eq!(
+                        content,
+                        generic_receipt(&author, schema, &refs, &payload, None)
+                    );
+                    assert_eq!(encoded_len(schema, &refs, &payload, None), content.len());
+
+                    let bytes = canonical_receipt(&author, schema, &refs, &payload, &signature);
+                    let expected =
+                        generic_receipt(&author, schema, &refs, &payload, Some(&signature));
+                    assert_eq!(bytes, expected);
+                    let len = encoded_len(schema, &refs, &payload, Some(&signature));
+                    assert_eq!(len, bytes.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_appends() {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let author = keypair.author();
+        let signature = keypair.sign(b"anything");
+        let refs = vec![ReceiptId::from_bytes([0xab; 32])];
+
+        let mut buf = b"prefix".to_vec();
+        canonical_receipt_into(&mut buf, &author, "test/v1", &refs, b"hello", &signature);
+        assert_eq!(&buf[..6], b"prefix");
+        assert_eq!(
+            &buf[6..],
+            canonical_receipt(&author, "test/v1", &refs, b"hello", &signature)
+        );
+
+        buf.clear();
+        canonical_content_into(&mut buf, &author, "test/v1", &refs, b"hello");
+        assert_eq!(buf, canonical_content(&author, "test/v1", &refs, b"hello"));
+    }
+
     #[test]
     fn test_canonical_deterministic() {
         let author = Keypair::from_seed(&[0x42; 32]).author();
@@ -424,6 +668,140 @@ mod tests {
         assert!(result.is_ok(), "receipt_bytes must be valid CBOR");
     }
 
+    /// Valid receipt bytes plus the raw encoded fields, for building variants.
+    fn sample_fields() -> (Vec<u8>, Vec<(&'static str, Vec<u8>)>) {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let author = keypair.author();
+        let refs = vec![ReceiptId::from_bytes([0xab; 32])];
+        let content = canonical_content(&author, "test/v1", &refs, b"hello");
+        let signature = keypair.sign(&sign_message(&content));
+        let bytes = canonical_receipt(&author, "test/v1", &refs, b"hello", &signature);
+
+        let mut refs_value = Vec::new();
+        encode_array(&mut refs_value, &[Value::Bytes(refs[0].0.to_vec())]);
+        let mut author_value = Vec::new();
+        encode_bytes(&mut author_value, &author.0);
+        let mut schema_value = Vec::new();
+        encode_text(&mut schema_value, "test/v1");
+        let mut payload_value = Vec::new();
+        encode_bytes(&mut payload_value, b"hello");
+        let mut signature_value = Vec::new();
+        encode_bytes(&mut signature_value, &signature.0);
+
+        let fields = vec![
+            (keys::REFS, refs_value),
+            (keys::AUTHOR, author_value),
+            (keys::SCHEMA, schema_value),
+            (keys::PAYLOAD, payload_value),
+            (keys::SIGNATURE, signature_value),
+        ];
+        (bytes, fields)
+    }
+
+    /// Encode a map with entries in the given order (no sorting).
+    fn raw_map(fields: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode_uint(&mut buf, 5, fields.len() as u64);
+        for (key, value) in fields {
+            encode_text(&mut buf, key);
+            buf.extend_from_slice(value);
+        }
+        buf
+    }
+
+    #[test]
+    fn test_decode_reencodes_to_input() {
+        let (bytes, fields) = sample_fields();
+        assert_eq!(raw_map(&fields), bytes);
+
+        let (author, schema, refs, payload, signature) = decode_receipt(&bytes).unwrap();
+        assert_eq!(
+            canonical_receipt(&author, &schema, &refs, &payload, &signature),
+            bytes
+        );
+    }
+
+    #[test]
+    fn test_decode_rejects_indefinite_length() {
+        let (bytes, _) = sample_fields();
+        let mut indefinite = vec![0xbf];
+        indefinite.extend_from_slice(&bytes[1..]);
+        indefinite.push(0xff);
+
+        assert!(matches!(
+            decode_receipt(&indefinite),
+            Err(Error::NonCanonical(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_non_minimal_length() {
+        let (bytes, _) = sample_fields();
+        // Map of 5 entries encoded with a 1-byte length argument
+        let mut non_minimal = vec![0xb8, 0x05];
+        non_minimal.extend_from_slice(&bytes[1..]);
+
+        assert!(matches!(
+            decode_receipt(&non_minimal),
+            Err(Error::NonCanonical(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_unsorted_keys() {
+        let (_, mut fields) = sample_fields();
+        fields.swap(0, 1); // "author" before "refs"
+
+        assert!(matches!(
+            decode_receipt(&raw_map(&fields)),
+            Err(Error::NonCanonical(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_duplicate_keys() {
+        let (_, mut fields) = sample_fields();
+        fields.insert(1, fields[0].clone());
+
+        assert!(matches!(
+            decode_receipt(&raw_map(&fields)),
+            Err(Error::NonCanonical(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_unknown_keys() {
+        let (_, mut fields) = sample_fields();
+        let mut value = Vec::new();
+        encode_bytes(&mut value, b"");
+        fields.insert(0, ("zz", value));
+
+        assert!(matches!(
+            decode_receipt(&raw_map(&fields)),
+            Err(Error::MalformedReceipt(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_trailing_bytes() {
+        let (mut bytes, _) = sample_fields();
+        bytes.push(0x00);
+
+        assert!(matches!(
+            decode_receipt(&bytes),
+            Err(Error::NonCanonical(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated() {
+        let (bytes, _) = sample_fields();
+
+        for len in 0..bytes.len() {
+            assert!(decode_receipt(&bytes[..len]).is_err());
+        }
+    }
+
     #[test]
     fn test_domain_separation_changes_signature() {
         let keypair = Keypair::from_seed(&[0x42; 32]);
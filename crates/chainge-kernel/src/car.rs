@@ -0,0 +1,178 @@
+//! CARv1 export for archiving receipts to IPFS (SPEC.md Appendix A).
+//!
+//! Each receipt becomes one block: its CID and its canonical receipt bytes.
+//! Refs hold receipt IDs, not CIDs, so IPFS sees the blocks as unlinked;
+//! the DAG is recovered by decoding them.
+
+use crate::canonical::canonical_receipt;
+use crate::crypto::Sha256Hash;
+use crate::dag::{topological_order, walk_refs, Direction};
+use crate::error::{Error, Result};
+use crate::receipt::{Receipt, ReceiptId};
+use crate::store::Store;
+
+/// Export `root` and its ancestors as a CARv1 archive with `root` as its root.
+///
+/// Blocks are written in topological order (ancestors first). Ancestors
+/// missing from the store are skipped; the root itself must be present.
+pub fn export_car<S: Store + ?Sized>(store: &S, root: &ReceiptId) -> Result<Vec<u8>> {
+    let root_receipt = store.get(root)?.ok_or(Error::RefNotFound(*root))?;
+
+    let mut ids = walk_refs(store, root, Direction::Ancestors, None)?;
+    ids.push(*root);
+
+    let mut car = Vec::new();
+    let header = car_header(&cid_and_bytes(&root_receipt).0);
+    write_varint(&mut car, header.len() as u64);
+    car.extend_from_slice(&header);
+
+    for id in topological_order(store, &ids)? {
+        // Missing ancestors have no bytes to export
+        let Some(receipt) = store.get(&id)? else {
+            continue;
+        };
+        let (cid, bytes) = cid_and_bytes(&receipt);
+        write_varint(&mut car, (cid.len() + bytes.len()) as u64);
+        car.extend_from_slice(&cid);
+        car.extend_from_slice(&bytes);
+    }
+
+    Ok(car)
+}
+
+fn cid_and_bytes(receipt: &Receipt) -> ([u8; 36], Vec<u8>) {
+    let bytes = canonical_receipt(
+        &receipt.author,
+        &receipt.schema,
+        &receipt.refs,
+        &receipt.payload,
+        &receipt.signature,
+    );
+    (Sha256Hash::hash(&bytes).to_cid_bytes(), bytes)
+}
+
+/// DAG-CBOR `{"roots": [CID(root)], "version": 1}`.
+fn car_header(root_cid: &[u8; 36]) -> Vec<u8> {
+    let mut header = Vec::with_capacity(60);
+    header.push(0xa2); // map(2)
+    header.extend_from_slice(b"\x65roots");
+    header.push(0x81); // array(1)
+    header.extend_from_slice(&[0xd8, 0x2a]); // tag(42): IPLD link
+    header.extend_from_slice(&[0x58, 0x25]); // bytes(37)
+    header.push(0x00); // identity multibase prefix
+    header.extend_from_slice(root_cid);
+    header.extend_from_slice(b"\x67version");
+    header.push(0x01);
+    header
+}
+
+/// Unsigned LEB128, as used for CAR section lengths.
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Keypair;
+    use crate::store::MemoryStore;
+
+    fn read_varint(data: &[u8], pos: &mut usize) -> usize {
+        let (mut value, mut shift) = (0usize, 0);
+        loop {
+            let byte = data[*pos];
+            *pos += 1;
+            value |= ((byte & 0x7f) as usize) << shift;
+            if byte < 0x80 {
+                return value;
+            }
+            shift += 7;
+        }
+    }
+
+    /// A CAR section: (cid, block).
+    type Section<'a> = (&'a [u8], &'a [u8]);
+
+    /// Split a CAR into its header and sections.
+    fn parse_car(car: &[u8]) -> (&[u8], Vec<Section<'_>>) {
+        let mut pos = 0;
+        let len = read_varint(car, &mut pos);
+        let header = &car[pos..pos + len];
+        pos += len;
+
+        let mut blocks = Vec::new();
+        while pos < car.len() {
+            let len = read_varint(car, &mut pos);
+            let section = &car[pos..pos + len];
+            blocks.push(section.split_at(36));
+            pos += len;
+        }
+        (header, blocks)
+    }
+
+    #[test]
+    fn test_export_chain() {
+        let store = MemoryStore::new();
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let a = Receipt::new(&keypair, "test/v1", vec![], b"A".to_vec()).unwrap();
+        let b = Receipt::new(&keypair, "test/v1", vec![a.id()], b"B".to_vec()).unwrap();
+        let unrelated = Receipt::new(&keypair, "test/v1", vec![], b"X".to_vec()).unwrap();
+        for r in [&a, &b, &unrelated] {
+            store.insert(r).unwrap();
+        }
+
+        let car = export_car(&store, &b.id()).unwrap();
+        let (header, blocks) = parse_car(&car);
+
+        let root_cid = Sha256Hash::from_cid(&b.cid()).unwrap().to_cid_bytes();
+        assert_eq!(header, car_header(&root_cid).as_slice());
+
+        assert_eq!(blocks.len(), 2);
+        for ((cid, data), receipt) in blocks.iter().zip([&a, &b]) {
+            assert_eq!(*data, receipt.to_bytes().as_slice());
+            assert_eq!(*cid, &Sha256Hash::hash(data).to_cid_bytes()[..]);
+            assert_eq!(Receipt::from_bytes(data).unwrap(), *receipt);
+        }
+    }
+
+    #[test]
+    fn test_export_skips_missing_ancestors() {
+        let store = MemoryStore::new();
+        let keypair = Keypair::generate();
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+        let receipt = Receipt::new(&keypair, "test/v1", vec![missing], vec![]).unwrap();
+        store.insert(&receipt).unwrap();
+
+        let car = export_car(&store, &receipt.id()).unwrap();
+        let (_, blocks) = parse_car(&car);
+        assert_eq!(blocks.len(), 1);
+    }
+
+    #[test]
+    fn test_export_requires_root() {
+        let store = MemoryStore::new();
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+        assert!(matches!(
+            export_car(&store, &missing),
+            Err(Error::RefNotFound(id)) if id == missing
+        ));
+    }
+
+    #[test]
+    fn test_varint() {
+        for (value, expected) in [
+            (0u64, &[0x00][..]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x80, 0x01]),
+            (300, &[0xac, 0x02]),
+        ] {
+            let mut out = Vec::new();
+            write_varint(&mut out, value);
+            assert_eq!(out, expected);
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Helpers for the blessed patterns in CONVENTIONS.md.
+//!
+//! These build ordinary receipts. The kernel attaches no special meaning to
+//! them; they exist so applications don't each re-implement the same rules.
+
+use crate::crypto::Keypair;
+use crate::error::{Error, Result};
+use crate::receipt::{Receipt, ReceiptId};
+use crate::store::Store;
+
+/// Schema URI for countersign receipts (CONVENTIONS.md §4).
+pub const COUNTERSIGN_SCHEMA: &str = "countersign/v1";
+
+/// Create a countersign receipt witnessing `target`.
+///
+/// The target must already be in the store, and the witness must be a
+/// different author than the target's. The receipt is returned, not inserted.
+pub fn countersign<S: Store + ?Sized>(
+    store: &S,
+    witness: &Keypair,
+    target: &ReceiptId,
+    payload: Vec<u8>,
+) -> Result<Receipt> {
+    let original = store.get(target)?.ok_or(Error::RefNotFound(*target))?;
+    if original.author == witness.author() {
+        return Err(Error::SelfCountersign);
+    }
+
+    Receipt::new(witness, COUNTERSIGN_SCHEMA, vec![*target], payload)
+}
+
+/// Collect countersignatures of `target` from the store, sorted by receipt ID.
+///
+/// Receipts by the target's own author are not countersignatures and are
+/// skipped. If the target itself is unknown, every countersign receipt that
+/// references it is returned.
+pub fn countersignatures<S: Store + ?Sized>(store: &S, target: &ReceiptId) -> Result<Vec<Receipt>> {
+    let original_author = store.get(target)?.map(|r| r.author);
+
+    let mut witnesses: Vec<Receipt> = store
+        .refs_to(target)?
+        .into_iter()
+        .filter(|r| r.schema == COUNTERSIGN_SCHEMA)
+        .filter(|r| Some(r.author) != original_author)
+        .collect();
+    witnesses.sort_by_cached_key(|r| r.id());
+
+    Ok(witnesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::MemoryStore;
+
+    #[test]
+    fn test_countersign() {
+        let store = MemoryStore::new();
+        let alice = Keypair::generate();
+        let org = Keypair::generate();
+
+        let claim = Receipt::new(
+            &alice,
+            "civic.presence/v1",
+            vec![],
+            b"I volunteered".to_vec(),
+        )
+        .unwrap();
+        store.insert(&claim).unwrap();
+
+        let witness = countersign(&store, &org, &claim.id(), b"confirmed".to_vec()).unwrap();
+
+        assert_eq!(witness.author, org.author());
+        assert_eq!(witness.schema, COUNTERSIGN_SCHEMA);
+        assert_eq!(witness.refs, vec![claim.id()]);
+        assert!(witness.verify().is_ok());
+    }
+
+    #[test]
+    fn test_countersign_requires_target() {
+        let store = MemoryStore::new();
+        let org = Keypair::generate();
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+
+        let result = countersign(&store, &org, &missing, vec![]);
+        assert!(matches!(result, Err(Error::RefNotFound(id)) if id == missing));
+    }
+
+    #[test]
+    fn test_countersign_rejects_self() {
+        let store = MemoryStore::new();
+        let alice = Keypair::generate();
+
+        let claim = Receipt::new(&alice, "test/v1", vec![], vec![]).unwrap();
+        store.insert(&claim).unwrap();
+
+        let result = countersign(&store, &alice, &claim.id(), vec![]);
+        assert!(matches!(result, Err(Error::SelfCountersign)));
+    }
+
+    #[test]
+    fn test_countersignatures() {
+        let store = MemoryStore::new();
+        let alice = Keypair::generate();
+        let org1 = Keypair::generate();
+        let org2 = Keypair::generate();
+
+        let claim = Receipt::new(&alice, "test/v1", vec![], b"claim".to_vec()).unwrap();
+        store.insert(&claim).unwrap();
+
+        let w1 = countersign(&store, &org1, &claim.id(), b"confirmed".to_vec()).unwrap();
+        let w2 = countersign(&store, &org2, &claim.id(), b"confirmed".to_vec()).unwrap();
+        store.insert(&w1).unwrap();
+        store.insert(&w2).unwrap();
+
+        // Not countersignatures: different schema, or the original author
+        let reply = Receipt::new(&org1, "reply/v1", vec![claim.id()], vec![]).unwrap();
+        let own = Receipt::new(&alice, COUNTERSIGN_SCHEMA, vec![claim.id()], vec![]).unwrap();
+        store.insert(&reply).unwrap();
+        store.insert(&own).unwrap();
+
+        let found = countersignatures(&store, &claim.id()).unwrap();
+        let mut expected = vec![w1.id(), w2.id()];
+        expected.sort();
+
+        assert_eq!(found.iter().map(|r| r.id()).collect::<Vec<_>>(), expected);
+    }
+}
@@ -8,6 +8,7 @@
 use ed25519_dalek::{Signature as DalekSignature, Signer, SigningKey, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
 use std::fmt;
+use zeroize::Zeroizing;
 
 use crate::error::{Error, Result};
 
@@ -43,15 +44,44 @@ impl Sha256Hash {
     ///
     /// Format: `b` + base32lower(0x01 || 0x71 || 0x12 || 0x20 || hash)
     pub fn to_cid(&self) -> String {
-        let mut cid_bytes = Vec::with_capacity(36);
-        cid_bytes.push(0x01); // CIDv1
-        cid_bytes.push(0x71); // dag-cbor codec
-        cid_bytes.push(0x12); // sha2-256 multihash
-        cid_bytes.push(0x20); // 32 bytes
-        cid_bytes.extend_from_slice(&self.0);
-
         // Base32 lower with 'b' prefix (multibase)
-        format!("b{}", base32_encode(&cid_bytes))
+        format!("b{}", base32_encode(&self.to_cid_bytes()))
+    }
+
+    /// Binary CIDv1 (dag-cbor, sha2-256), without multibase prefix.
+    ///
+    /// Format: `0x01 || 0x71 || 0x12 || 0x20 || hash`
+    pub fn to_cid_bytes(&self) -> [u8; 36] {
+        let mut cid_bytes = [0u8; 36];
+        cid_bytes[0] = 0x01; // CIDv1
+        cid_bytes[1] = 0x71; // dag-cbor codec
+        cid_bytes[2] = 0x12; // sha2-256 multihash
+        cid_bytes[3] = 0x20; // 32 bytes
+        cid_bytes[4..].copy_from_slice(&self.0);
+        cid_bytes
+    }
+
+    /// Parse a CIDv1 produced by [`to_cid`](Self::to_cid).
+    ///
+    /// Only the kernel's own form is accepted: base32lower multibase, CIDv1,
+    /// dag-cbor codec, sha2-256 multihash with a 32-byte digest.
+    pub fn from_cid(cid: &str) -> Result<Self> {
+        let encoded = cid
+            .strip_prefix('b')
+            .ok_or_else(|| Error::InvalidCid("expected base32lower multibase prefix 'b'".into()))?;
+        let cid_bytes = base32_decode(encoded)
+            .ok_or_else(|| Error::InvalidCid("invalid base32lower".into()))?;
+
+        match cid_bytes.as_slice() {
+            [0x01, 0x71, 0x12, 0x20, digest @ ..] if digest.len() == 32 => {
+                let mut arr = [0u8; 32];
+                arr.copy_from_slice(digest);
+                Ok(Self(arr))
+            }
+            [0x01, 0x71, ..] => Err(Error::InvalidCid("expected sha2-256 multihash".into())),
+            [0x01, ..] => Err(Error::InvalidCid("expected dag-cbor codec".into())),
+            _ => Err(Error::InvalidCid("expected CIDv1".into())),
+        }
     }
 }
 
@@ -179,6 +209,9 @@ impl From<[u8; 64]> for Signature {
 }
 
 /// A keypair for signing receipts.
+///
+/// The secret key is zeroized when the keypair (or any clone of it) is
+/// dropped. `Debug` prints only the author.
 #[derive(Clone)]
 pub struct Keypair {
     signing_key: SigningKey,
@@ -198,6 +231,13 @@ impl Keypair {
         Self { signing_key }
     }
 
+    /// The 32-byte secret seed, for persisting the keypair.
+    ///
+    /// The returned buffer is zeroized on drop; avoid copying out of it.
+    pub fn to_seed(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.signing_key.to_bytes())
+    }
+
     /// Get the public key (author).
     pub fn author(&self) -> Author {
         Author(self.signing_key.verifying_key().to_bytes())
@@ -242,6 +282,35 @@ fn base32_encode(data: &[u8]) -> String {
     result
 }
 
+// RFC 4648 Base32 decoding (lowercase, no padding)
+fn base32_decode(s: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len() * 5 / 8);
+    let mut buffer: u64 = 0;
+    let mut bits_in_buffer = 0;
+
+    for c in s.bytes() {
+        let value = match c {
+            b'a'..=b'z' => c - b'a',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | (value as u64);
+        bits_in_buffer += 5;
+
+        if bits_in_buffer >= 8 {
+            bits_in_buffer -= 8;
+            result.push((buffer >> bits_in_buffer) as u8);
+        }
+    }
+
+    // Leftover bits are padding and must be zero
+    if bits_in_buffer >= 5 || buffer & ((1 << bits_in_buffer) - 1) != 0 {
+        return None;
+    }
+
+    Some(result)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -266,6 +335,7 @@ mod tests {
         let kp1 = Keypair::from_seed(&seed);
         let kp2 = Keypair::from_seed(&seed);
         assert_eq!(kp1.author(), kp2.author());
+        assert_eq!(*kp1.to_seed(), seed);
     }
 
     #[test]
@@ -302,4 +372,50 @@ mod tests {
         assert_eq!(base32_encode(b"fooba"), "mzxw6ytb");
         assert_eq!(base32_encode(b"foobar"), "mzxw6ytboi");
     }
+
+    #[test]
+    fn test_base32_decode() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            assert_eq!(base32_decode(&base32_encode(input)).unwrap(), input);
+        }
+
+        // Non-zero padding bits and invalid lengths have no canonical encoding
+        assert_eq!(base32_decode("mz"), None);
+        assert_eq!(base32_decode("m"), None);
+        assert_eq!(base32_decode("MY"), None);
+    }
+
+    #[test]
+    fn test_cid_roundtrip() {
+        let hash = Sha256Hash::hash(b"hello");
+        let cid = hash.to_cid();
+
+        assert_eq!(Sha256Hash::from_cid(&cid).unwrap(), hash);
+    }
+
+    #[test]
+    fn test_from_cid_rejects_other_forms() {
+        let hash = Sha256Hash::hash(b"hello");
+        let cid = hash.to_cid();
+
+        // Wrong multibase
+        assert!(matches!(
+            Sha256Hash::from_cid(&cid[1..]),
+            Err(Error::InvalidCid(_))
+        ));
+
+        // Raw codec (0x55) instead of dag-cbor
+        let mut raw = vec![0x01, 0x55, 0x12, 0x20];
+        raw.extend_from_slice(&hash.0);
+        assert!(matches!(
+            Sha256Hash::from_cid(&format!("b{}", base32_encode(&raw))),
+            Err(Error::InvalidCid(_))
+        ));
+
+        // Truncated digest
+        assert!(matches!(
+            Sha256Hash::from_cid(&format!("b{}", base32_encode(&raw[..20]))),
+            Err(Error::InvalidCid(_))
+        ));
+    }
 }
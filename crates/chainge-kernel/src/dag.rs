@@ -0,0 +1,422 @@
+//! Traversal of the refs DAG (see docs/DAG_SEMANTICS.md).
+//!
+//! Refs point from a receipt to its causal predecessors. Walking refs gives
+//! ancestors ("everything this depends on"); walking [`Store::refs_to`]
+//! gives descendants ("everything that builds on this").
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::error::{Error, Result};
+use crate::receipt::{Receipt, ReceiptId};
+use crate::store::Store;
+
+/// Which way to walk the refs DAG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Follow refs: receipts that are causally prior.
+    Ancestors,
+    /// Follow reverse refs: receipts that reference this one.
+    Descendants,
+}
+
+/// Walk the DAG from `start`, breadth-first.
+///
+/// Returns every reachable receipt ID once, nearest first, excluding `start`.
+/// Ties at the same depth are in ID order. `depth_limit` bounds the number of
+/// hops (`Some(1)` = direct refs only; `None` = unbounded).
+///
+/// Ancestors that are referenced but not in the store are still returned
+/// (the DAG may have gaps until synced), but cannot be walked past.
+pub fn walk_refs<S: Store + ?Sized>(
+    store: &S,
+    start: &ReceiptId,
+    direction: Direction,
+    depth_limit: Option<usize>,
+) -> Result<Vec<ReceiptId>> {
+    let mut seen = HashSet::from([*start]);
+    let mut queue = VecDeque::from([(*start, 0usize)]);
+    let mut found = Vec::new();
+
+    while let Some((id, depth)) = queue.pop_front() {
+        if depth_limit.is_some_and(|limit| depth >= limit) {
+            continue;
+        }
+
+        for next in neighbors(store, &id, direction)? {
+            if seen.insert(next) {
+                found.push(next);
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Order receipts so that each comes after every ref it has within `ids`.
+///
+/// Refs to receipts outside `ids` (or missing from the store) are ignored.
+/// Among receipts whose refs are all satisfied, the smallest ID goes first,
+/// so the order is deterministic across nodes holding the same set.
+///
+/// Honest receipts cannot form a cycle (an ID commits to its refs), so a
+/// cycle means some receipt is stored under an ID it doesn't hash to; that
+/// is reported as [`Error::MalformedReceipt`].
+pub fn topological_order<S: Store + ?Sized>(
+    store: &S,
+    ids: &[ReceiptId],
+) -> Result<Vec<ReceiptId>> {
+    let members: HashSet<ReceiptId> = ids.iter().copied().collect();
+
+    // Count unsatisfied refs per receipt; index who is waiting on whom
+    let mut pending: HashMap<ReceiptId, usize> = HashMap::new();
+    let mut waiting: HashMap<ReceiptId, Vec<ReceiptId>> = HashMap::new();
+    for id in &members {
+        let refs = match store.get(id)? {
+            Some(receipt) => receipt.refs,
+            None => vec![],
+        };
+        let mut count = 0;
+        for r in refs.into_iter().filter(|r| members.contains(r)) {
+            waiting.entry(r).or_default().push(*id);
+            count += 1;
+        }
+        pending.insert(*id, count);
+    }
+
+    let mut ready: BTreeSet<ReceiptId> = pending
+        .iter()
+        .filter(|(_, count)| **count == 0)
+        .map(|(id, _)| *id)
+        .collect();
+    let mut order = Vec::with_capacity(members.len());
+
+    while let Some(id) = ready.pop_first() {
+        order.push(id);
+        for child in waiting.remove(&id).unwrap_or_default() {
+            let count = pending.get_mut(&child).expect("child is a member");
+            *count -= 1;
+            if *count == 0 {
+                ready.insert(child);
+            }
+        }
+    }
+
+    if order.len() != members.len() {
+        return Err(Error::MalformedReceipt("refs cycle".into()));
+    }
+    Ok(order)
+}
+
+/// Refs of `receipt` that are not in the store, in ID order.
+///
+/// Call after [`Store::insert`] to learn whether a receipt arrived ahead of
+/// its ancestors. Whether it filled a gap is `!store.refs_to(&id)?.is_empty()`.
+pub fn missing_refs<S: Store + ?Sized>(store: &S, receipt: &Receipt) -> Result<Vec<ReceiptId>> {
+    let mut missing = Vec::new();
+    // Refs are stored sorted
+    for r in &receipt.refs {
+        if !store.has(r)? {
+            missing.push(*r);
+        }
+    }
+    Ok(missing)
+}
+
+/// Every ID referenced by a stored receipt but not itself stored, sorted.
+///
+/// These are the receipts a node still needs to complete its DAG.
+pub fn gaps<S: Store + ?Sized>(store: &S) -> Result<Vec<ReceiptId>> {
+    let mut gaps = BTreeSet::new();
+    for id in store.all_ids()? {
+        if let Some(receipt) = store.get(&id)? {
+            gaps.extend(missing_refs(store, &receipt)?);
+        }
+    }
+    Ok(gaps.into_iter().collect())
+}
+
+/// Check that `id` and all of its ancestors are present and valid.
+///
+/// Every receipt in the refs closure is re-verified and checked against the
+/// ID it is stored under. Receipts are checked in [`topological_order`], so
+/// the error returned is the earliest point where the history is broken:
+/// [`Error::RefNotFound`] for a gap, or the receipt's validation error.
+pub fn verify_ancestry<S: Store + ?Sized>(store: &S, id: &ReceiptId) -> Result<()> {
+    let mut ids = walk_refs(store, id, Direction::Ancestors, None)?;
+    ids.push(*id);
+
+    for id in topological_order(store, &ids)? {
+        let receipt = store.get(&id)?.ok_or(Error::RefNotFound(id))?;
+        if receipt.id() != id {
+            return Err(Error::MalformedReceipt(format!(
+                "receipt stored under {} hashes to {}",
+                id.to_hex(),
+                receipt.id().to_hex()
+            )));
+        }
+        receipt.verify()?;
+    }
+
+    Ok(())
+}
+
+/// Direct neighbors of `id` in the given direction, in ID order.
+fn neighbors<S: Store + ?Sized>(
+    store: &S,
+    id: &ReceiptId,
+    direction: Direction,
+) -> Result<Vec<ReceiptId>> {
+    match direction {
+        // Refs are stored sorted
+        Direction::Ancestors => Ok(store.get(id)?.map(|r| r.refs).unwrap_or_default()),
+        Direction::Descendants => {
+            let mut ids: Vec<ReceiptId> = store.refs_to(id)?.iter().map(|r| r.id()).collect();
+            ids.sort();
+            Ok(ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Keypair;
+    use crate::store::MemoryStore;
+
+    /// Build the DAG from DAG_SEMANTICS.md:
+    ///
+    /// A <- B <- C, B <- D, F -> [A, C, D]
+    fn sample_dag(store: &MemoryStore) -> [ReceiptId; 5] {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let add = |refs: Vec<ReceiptId>, payload: &[u8]| {
+            let receipt = Receipt::new(&keypair, "test/v1", refs, payload.to_vec()).unwrap();
+            store.insert(&receipt).unwrap();
+            receipt.id()
+        };
+
+        let a = add(vec![], b"A");
+        let b = add(vec![a], b"B");
+        let c = add(vec![b], b"C");
+        let d = add(vec![b], b"D");
+        let f = add(vec![a, c, d], b"F");
+        [a, b, c, d, f]
+    }
+
+    fn sorted(mut ids: Vec<ReceiptId>) -> Vec<ReceiptId> {
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let store = MemoryStore::new();
+        let [a, b, c, d, f] = sample_dag(&store);
+
+        let found = walk_refs(&store, &f, Direction::Ancestors, None).unwrap();
+        assert_eq!(found.len(), 4);
+        assert_eq!(&sorted(found[..3].to_vec()), &sorted(vec![a, c, d]));
+        assert_eq!(found[3], b);
+
+        // Depth 1 = direct refs only
+        let direct = walk_refs(&store, &f, Direction::Ancestors, Some(1)).unwrap();
+        assert_eq!(direct, sorted(vec![a, c, d]));
+
+        // Genesis has no ancestors
+        assert!(walk_refs(&store, &a, Direction::Ancestors, None)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_descendants() {
+        let store = MemoryStore::new();
+        let [a, b, c, d, f] = sample_dag(&store);
+
+        let found = walk_refs(&store, &b, Direction::Descendants, None).unwrap();
+        assert_eq!(sorted(found.clone()), sorted(vec![c, d, f]));
+        assert_eq!(found[2], f);
+
+        let found = walk_refs(&store, &a, Direction::Descendants, Some(1)).unwrap();
+        assert_eq!(found, sorted(vec![b, f]));
+    }
+
+    #[test]
+    fn test_missing_ancestor_is_reported() {
+        let store = MemoryStore::new();
+        let keypair = Keypair::generate();
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+        let receipt = Receipt::new(&keypair, "test/v1", vec![missing], vec![]).unwrap();
+        store.insert(&receipt).unwrap();
+
+        let found = walk_refs(&store, &receipt.id(), Direction::Ancestors, None).unwrap();
+        assert_eq!(found, vec![missing]);
+    }
+
+    #[test]
+    fn test_missing_refs_and_gaps() {
+        let full = MemoryStore::new();
+        let [a, b, c, d, f] = sample_dag(&full);
+        assert!(gaps(&full).unwrap().is_empty());
+
+        // Deliver B and F without their ancestors
+        let partial = MemoryStore::new();
+        let b_receipt = full.get(&b).unwrap().unwrap();
+        let f_receipt = full.get(&f).unwrap().unwrap();
+        partial.insert(&b_receipt).unwrap();
+        partial.insert(&f_receipt).unwrap();
+
+        assert_eq!(missing_refs(&partial, &b_receipt).unwrap(), vec![a]);
+        assert_eq!(gaps(&partial).unwrap(), sorted(vec![a, c, d]));
+
+        // C fills a gap (F references it) and opens none (B is present)
+        let c_receipt = full.get(&c).unwrap().unwrap();
+        partial.insert(&c_receipt).unwrap();
+        assert!(missing_refs(&partial, &c_receipt).unwrap().is_empty());
+        assert!(!partial.refs_to(&c).unwrap().is_empty());
+        assert_eq!(gaps(&partial).unwrap(), sorted(vec![a, d]));
+    }
+
+    #[test]
+    fn test_verify_ancestry() {
+        let store = MemoryStore::new();
+        let [a, _, _, _, f] = sample_dag(&store);
+        assert!(verify_ancestry(&store, &f).is_ok());
+        assert!(verify_ancestry(&store, &a).is_ok());
+
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+        assert!(matches!(
+            verify_ancestry(&store, &missing),
+            Err(Error::RefNotFound(id)) if id == missing
+        ));
+    }
+
+    #[test]
+    fn test_verify_ancestry_reports_gap_and_forgery() {
+        let store = MemoryStore::new();
+        let keypair = Keypair::generate();
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+
+        // MemoryStore doesn't verify on insert, so a forged receipt can sit in it
+        let mut forged = Receipt::new(&keypair, "test/v1", vec![], b"real".to_vec()).unwrap();
+        forged.payload = b"forged".to_vec();
+        store.insert(&forged).unwrap();
+
+        let child = Receipt::new(&keypair, "test/v1", vec![forged.id()], vec![]).unwrap();
+        store.insert(&child).unwrap();
+        assert!(matches!(
+            verify_ancestry(&store, &child.id()),
+            Err(Error::InvalidSignature)
+        ));
+
+        let gap = Receipt::new(&keypair, "test/v1", vec![missing], vec![]).unwrap();
+        store.insert(&gap).unwrap();
+        assert!(matches!(
+            verify_ancestry(&store, &gap.id()),
+            Err(Error::RefNotFound(id)) if id == missing
+        ));
+    }
+
+    /// Store that files receipts under whatever ID it is given.
+    #[derive(Default)]
+    struct MislabeledStore(std::sync::RwLock<HashMap<ReceiptId, Receipt>>);
+
+    impl MislabeledStore {
+        fn put(&self, id: ReceiptId, receipt: Receipt) {
+            self.0.write().unwrap().insert(id, receipt);
+        }
+    }
+
+    impl Store for MislabeledStore {
+        fn insert(&self, receipt: &Receipt) -> Result<crate::store::InsertResult> {
+            self.put(receipt.id(), receipt.clone());
+            Ok(crate::store::InsertResult::Inserted)
+        }
+        fn get(&self, id: &ReceiptId) -> Result<Option<Receipt>> {
+            Ok(self.0.read().unwrap().get(id).cloned())
+        }
+        fn has(&self, id: &ReceiptId) -> Result<bool> {
+            Ok(self.0.read().unwrap().contains_key(id))
+        }
+        fn by_author(&self, author: &crate::crypto::Author) -> Result<Vec<Receipt>> {
+            let map = self.0.read().unwrap();
+            Ok(map
+                .values()
+                .filter(|r| r.author == *author)
+                .cloned()
+                .collect())
+        }
+        fn refs_to(&self, id: &ReceiptId) -> Result<Vec<Receipt>> {
+            let map = self.0.read().unwrap();
+            Ok(map
+                .values()
+                .filter(|r| r.refs.contains(id))
+                .cloned()
+                .collect())
+        }
+        fn all_ids(&self) -> Result<Vec<ReceiptId>> {
+            Ok(self.0.read().unwrap().keys().copied().collect())
+        }
+        fn count(&self) -> Result<usize> {
+            Ok(self.0.read().unwrap().len())
+        }
+    }
+
+    #[test]
+    fn test_verify_ancestry_rejects_cycle() {
+        let store = MislabeledStore::default();
+        let keypair = Keypair::generate();
+        let p = ReceiptId::from_bytes([0x01; 32]);
+        let q = ReceiptId::from_bytes([0x02; 32]);
+
+        // Each receipt refs the other's (false) ID: p -> q -> p
+        store.put(
+            p,
+            Receipt::new(&keypair, "test/v1", vec![q], b"P".to_vec()).unwrap(),
+        );
+        store.put(
+            q,
+            Receipt::new(&keypair, "test/v1", vec![p], b"Q".to_vec()).unwrap(),
+        );
+
+        assert!(matches!(
+            topological_order(&store, &[p, q]),
+            Err(Error::MalformedReceipt(_))
+        ));
+        assert!(matches!(
+            verify_ancestry(&store, &p),
+            Err(Error::MalformedReceipt(_))
+        ));
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let store = MemoryStore::new();
+        let [a, b, c, d, f] = sample_dag(&store);
+
+        let order = topological_order(&store, &store.all_ids().unwrap()).unwrap();
+        assert_eq!(order.len(), 5);
+
+        let pos = |id: &ReceiptId| order.iter().position(|x| x == id).unwrap();
+        assert!(pos(&a) < pos(&b));
+        assert!(pos(&b) < pos(&c));
+        assert!(pos(&b) < pos(&d));
+        assert!(pos(&c) < pos(&f));
+        assert!(pos(&d) < pos(&f));
+
+        // Deterministic regardless of input order
+        let mut reversed = store.all_ids().unwrap();
+        reversed.reverse();
+        assert_eq!(topological_order(&store, &reversed).unwrap(), order);
+    }
+
+    #[test]
+    fn test_topological_order_subset() {
+        let store = MemoryStore::new();
+        let [_, b, c, _, f] = sample_dag(&store);
+
+        // Refs outside the subset are ignored
+        let order = topological_order(&store, &[f, c, b]).unwrap();
+        assert_eq!(order, vec![b, c, f]);
+    }
+}
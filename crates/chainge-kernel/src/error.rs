@@ -37,10 +37,26 @@ pub enum Error {
     #[error("invalid public key")]
     InvalidPublicKey,
 
+    /// A referenced receipt is not in the store.
+    #[error("referenced receipt not found: {0}")]
+    RefNotFound(crate::receipt::ReceiptId),
+
+    /// Countersigner is the author of the receipt being countersigned.
+    #[error("countersigner must differ from the original author")]
+    SelfCountersign,
+
+    /// CID string is not a kernel CIDv1.
+    #[error("invalid cid: {0}")]
+    InvalidCid(String),
+
     /// Malformed receipt bytes.
     #[error("malformed receipt: {0}")]
     MalformedReceipt(String),
 
+    /// CBOR is well-formed but not in canonical form.
+    #[error("non-canonical encoding: {0}")]
+    NonCanonical(String),
+
     /// CBOR decoding error.
     #[error("decoding error: {0}")]
     DecodingError(String),
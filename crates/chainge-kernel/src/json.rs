@@ -0,0 +1,305 @@
+//! Human-readable JSON representation of receipts.
+//!
+//! JSON is a *view* of a receipt for debugging, logging, HTTP APIs, and
+//! tooling that cannot easily consume CBOR. Signatures and IDs are always
+//! computed over the canonical CBOR bytes, never over the JSON.
+//!
+//! Layout (keys sorted, no whitespace, all binary fields lowercase hex):
+//!
+//! ```text
+//! {"author":"<64 hex>","payload":"<hex>","refs":["<64 hex>",...],"schema":"<text>","signature":"<128 hex>"}
+//! ```
+//!
+//! - `refs` is always present (empty array if none)
+//! - Exactly 5 keys; unknown or repeated keys are rejected on decode
+//!
+//! Decoding accepts any key order and insignificant whitespace; encoding
+//! always produces the layout above.
+
+use std::fmt;
+
+use serde::de::{Deserialize, Deserializer, Error as _, MapAccess, Visitor};
+use serde_json::{Map, Value};
+
+// JSON object keys are the CBOR keys
+use crate::canonical::{keys, DecodedReceipt};
+use crate::crypto::{Author, Signature};
+use crate::error::{Error, Result};
+use crate::receipt::ReceiptId;
+
+/// Encode receipt fields as a compact JSON string.
+pub fn encode_json(
+    author: &Author,
+    schema: &str,
+    refs: &[ReceiptId],
+    payload: &[u8],
+    signature: &Signature,
+) -> String {
+    encode_json_value(author, schema, refs, payload, signature).to_string()
+}
+
+/// Encode receipt fields as a JSON value, for embedding in larger documents.
+pub(crate) fn encode_json_value(
+    author: &Author,
+    schema: &str,
+    refs: &[ReceiptId],
+    payload: &[u8],
+    signature: &Signature,
+) -> Value {
+    // serde_json::Map is ordered by key, so output is deterministic
+    let mut map = Map::new();
+    map.insert(keys::AUTHOR.into(), Value::String(author.to_hex()));
+    map.insert(keys::PAYLOAD.into(), Value::String(hex::encode(payload)));
+    map.insert(
+        keys::REFS.into(),
+        Value::Array(refs.iter().map(|r| Value::String(r.to_hex())).collect()),
+    );
+    map.insert(keys::SCHEMA.into(), Value::String(schema.to_string()));
+    map.insert(keys::SIGNATURE.into(), Value::String(signature.to_hex()));
+
+    Value::Object(map)
+}
+
+/// Decode receipt fields from a JSON string.
+///
+/// Only checks the layout; semantic validation is done by the caller.
+pub fn decode_json(s: &str) -> Result<DecodedReceipt> {
+    let UniqueKeys(map) = serde_json::from_str(s).map_err(|e| {
+        if e.is_data() {
+            Error::MalformedReceipt(e.to_string())
+        } else {
+            Error::DecodingError(e.to_string())
+        }
+    })?;
+
+    // Reject unknown keys
+    if let Some(key) = map.keys().find(|k| {
+        ![
+            keys::AUTHOR,
+            keys::PAYLOAD,
+            keys::REFS,
+            keys::SCHEMA,
+            keys::SIGNATURE,
+        ]
+        .contains(&k.as_str())
+    }) {
+        return Err(Error::MalformedReceipt(format!("unknown key: {key}")));
+    }
+
+    let author = match map.get(keys::AUTHOR) {
+        Some(Value::String(s)) => Author::from_bytes(
+            decode_hex_array(s).ok_or_else(|| Error::MalformedReceipt("invalid author".into()))?,
+        ),
+        _ => return Err(Error::MalformedReceipt("invalid author".into())),
+    };
+
+    let schema = match map.get(keys::SCHEMA) {
+        Some(Value::String(s)) => s.clone(),
+        _ => return Err(Error::MalformedReceipt("invalid schema".into())),
+    };
+
+    // refs must be present, may be empty
+    let refs = match map.get(keys::REFS) {
+        Some(Value::Array(arr)) => {
+            let mut refs = Vec::with_capacity(arr.len());
+            for item in arr {
+                match item {
+                    Value::String(s) => match decode_hex_array(s) {
+                        Some(bytes) => refs.push(ReceiptId::from_bytes(bytes)),
+                        None => return Err(Error::MalformedReceipt("invalid ref".into())),
+                    },
+                    _ => return Err(Error::MalformedReceipt("invalid ref".into())),
+                }
+            }
+            refs
+        }
+        _ => return Err(Error::MalformedReceipt("missing or invalid refs".into())),
+    };
+
+    let payload = match map.get(keys::PAYLOAD) {
+        Some(Value::String(s)) => {
+            decode_hex(s).ok_or_else(|| Error::MalformedReceipt("invalid payload".into()))?
+        }
+        _ => return Err(Error::MalformedReceipt("invalid payload".into())),
+    };
+
+    let signature = match map.get(keys::SIGNATURE) {
+        Some(Value::String(s)) => Signature::from_bytes(
+            decode_hex_array(s)
+                .ok_or_else(|| Error::MalformedReceipt("invalid signature".into()))?,
+        ),
+        _ => return Err(Error::MalformedReceipt("invalid signature".into())),
+    };
+
+    Ok((author, schema, refs, payload, signature))
+}
+
+/// Decode lowercase hex only, as Appendix B requires.
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.bytes().any(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+    hex::decode(s).ok()
+}
+
+/// A top-level JSON object that rejects repeated keys.
+///
+/// `serde_json::Value` keeps the last of any duplicates, which would let
+/// `{"schema":"a",...,"schema":"b"}` through as a five-key receipt.
+struct UniqueKeys(Map<String, Value>);
+
+impl<'de> Deserialize<'de> for UniqueKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct UniqueKeysVisitor;
+
+        impl<'de> Visitor<'de> for UniqueKeysVisitor {
+            type Value = UniqueKeys;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> std::result::Result<UniqueKeys, A::Error> {
+                let mut map = Map::new();
+                while let Some(key) = access.next_key::<String>()? {
+                    if map.contains_key(&key) {
+                        return Err(A::Error::custom(format!("duplicate key: {key}")));
+                    }
+                    let value = access.next_value()?;
+                    map.insert(key, value);
+                }
+                Ok(UniqueKeys(map))
+            }
+        }
+
+        deserializer.deserialize_map(UniqueKeysVisitor)
+    }
+}
+
+/// Decode lowercase hex into a fixed-size array.
+fn decode_hex_array<const N: usize>(s: &str) -> Option<[u8; N]> {
+    decode_hex(s)?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::Keypair;
+
+    #[test]
+    fn test_layout() {
+        let author = Keypair::from_seed(&[0x42; 32]).author();
+        let refs = vec![ReceiptId::from_bytes([0xab; 32])];
+        let signature = Signature::from_bytes([0x01; 64]);
+
+        let json = encode_json(&author, "test/v1", &refs, b"hi", &signature);
+
+        let expected = format!(
+            r#"{{"author":"{}","payload":"6869","refs":["{}"],"schema":"test/v1","signature":"{}"}}"#,
+            author.to_hex(),
+            "ab".repeat(32),
+            "01".repeat(64),
+        );
+        assert_eq!(json, expected);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let author = Keypair::from_seed(&[0x42; 32]).author();
+        let refs = vec![ReceiptId::from_bytes([0xab; 32])];
+        let signature = Signature::from_bytes([0x01; 64]);
+
+        let json = encode_json(&author, "test/v1", &refs, b"hello", &signature);
+        let (dec_author, dec_schema, dec_refs, dec_payload, dec_sig) = decode_json(&json).unwrap();
+
+        assert_eq!(author, dec_author);
+        assert_eq!("test/v1", dec_schema);
+        assert_eq!(refs, dec_refs);
+        assert_eq!(b"hello".to_vec(), dec_payload);
+        assert_eq!(signature, dec_sig);
+    }
+
+    #[test]
+    fn test_reject_unknown_key() {
+        let author = Keypair::from_seed(&[0x42; 32]).author();
+        let json = encode_json(
+            &author,
+            "test/v1",
+            &[],
+            b"",
+            &Signature::from_bytes([0; 64]),
+        );
+        let json = json.replacen('{', r#"{"extra":1,"#, 1);
+
+        assert!(matches!(
+            decode_json(&json),
+            Err(Error::MalformedReceipt(_))
+        ));
+    }
+
+    #[test]
+    fn test_reject_duplicate_key() {
+        let author = Keypair::from_seed(&[0x42; 32]).author();
+        let json = encode_json(
+            &author,
+            "test/v1",
+            &[],
+            b"",
+            &Signature::from_bytes([0; 64]),
+        );
+        let json = json.replacen('{', r#"{"schema":"bogus","#, 1);
+
+        assert!(matches!(
+            decode_json(&json),
+            Err(Error::MalformedReceipt(msg)) if msg.contains("duplicate key: schema")
+        ));
+    }
+
+    #[test]
+    fn test_reject_non_object() {
+        assert!(matches!(
+            decode_json("[1,2]"),
+            Err(Error::MalformedReceipt(_))
+        ));
+        assert!(matches!(decode_json("{"), Err(Error::DecodingError(_))));
+    }
+
+    #[test]
+    fn test_reject_missing_refs() {
+        let author = Keypair::from_seed(&[0x42; 32]).author();
+        let json = encode_json(
+            &author,
+            "test/v1",
+            &[],
+            b"",
+            &Signature::from_bytes([0; 64]),
+        );
+        let json = json.replace(r#""refs":[],"#, "");
+
+        assert!(matches!(
+            decode_json(&json),
+            Err(Error::MalformedReceipt(_))
+        ));
+    }
+
+    #[test]
+    fn test_reject_uppercase_hex() {
+        let author = Keypair::from_seed(&[0x42; 32]).author();
+        let json = encode_json(
+            &author,
+            "test/v1",
+            &[],
+            b"\xab",
+            &Signature::from_bytes([0; 64]),
+        );
+        let json = json.replace(r#""payload":"ab""#, r#""payload":"AB""#);
+
+        assert!(matches!(
+            decode_json(&json),
+            Err(Error::MalformedReceipt(_))
+        ));
+    }
+}
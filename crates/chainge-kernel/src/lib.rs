@@ -35,16 +35,28 @@
 //! ```
 
 mod canonical;
+mod car;
+mod conventions;
 mod crypto;
+mod dag;
 mod error;
+mod json;
+mod provenance;
 mod receipt;
 mod store;
 
-pub use canonical::{canonical_content, canonical_receipt, sign_message, ID_DOMAIN, SIGN_DOMAIN};
+pub use canonical::{
+    canonical_content, canonical_content_into, canonical_receipt, canonical_receipt_into,
+    sign_message, ID_DOMAIN, SIGN_DOMAIN,
+};
+pub use car::export_car;
+pub use conventions::{countersign, countersignatures, COUNTERSIGN_SCHEMA};
 pub use crypto::{Author, Keypair, Sha256Hash, Signature};
+pub use dag::{gaps, missing_refs, topological_order, verify_ancestry, walk_refs, Direction};
 pub use error::{Error, Result};
+pub use provenance::{provenance, ProvenanceBundle};
 pub use receipt::{Receipt, ReceiptId};
-pub use store::{sync, InsertResult, MemoryStore, Store, SyncReport};
+pub use store::{migrate, sync, InsertResult, MemoryStore, MigrationReport, Store, SyncReport};
 
 /// Maximum schema URI length in bytes.
 pub const MAX_SCHEMA_LEN: usize = 256;
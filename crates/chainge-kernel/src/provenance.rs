@@ -0,0 +1,196 @@
+//! Self-contained evidence for a single receipt.
+//!
+//! A [`ProvenanceBundle`] carries a receipt, its full refs closure, and its
+//! countersignatures (CONVENTIONS.md §4), so it can be verified by someone
+//! who has no access to the store it came from.
+//!
+//! JSON form (keys sorted, receipts in their Appendix B JSON view):
+//!
+//! ```text
+//! {"receipts":[<receipt>,...],"target":"<64 hex>"}
+//! ```
+//!
+//! `receipts` lists ancestors first (topological order), then the target,
+//! then countersignatures sorted by ID.
+
+use serde_json::{Map, Value};
+
+use crate::conventions::countersignatures;
+use crate::dag::{topological_order, verify_ancestry, walk_refs, Direction};
+use crate::error::{Error, Result};
+use crate::receipt::{Receipt, ReceiptId};
+use crate::store::{MemoryStore, Store};
+
+/// A receipt with everything needed to verify it independently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenanceBundle {
+    /// The receipt this bundle is about.
+    pub target: ReceiptId,
+    /// Ancestors, the target, then countersignatures.
+    pub receipts: Vec<Receipt>,
+}
+
+/// Collect the provenance of `target` from `store`.
+///
+/// Fails with [`Error::RefNotFound`] if the target or any ancestor is
+/// missing: a bundle with gaps could not be verified elsewhere.
+pub fn provenance<S: Store + ?Sized>(store: &S, target: &ReceiptId) -> Result<ProvenanceBundle> {
+    verify_ancestry(store, target)?;
+
+    let mut ids = walk_refs(store, target, Direction::Ancestors, None)?;
+    ids.push(*target);
+
+    let mut receipts = Vec::with_capacity(ids.len());
+    for id in topological_order(store, &ids)? {
+        receipts.push(store.get(&id)?.ok_or(Error::RefNotFound(id))?);
+    }
+    receipts.extend(countersignatures(store, target)?);
+
+    Ok(ProvenanceBundle {
+        target: *target,
+        receipts,
+    })
+}
+
+impl ProvenanceBundle {
+    /// Verify every receipt and check that the target's refs closure is
+    /// complete.
+    pub fn verify(&self) -> Result<()> {
+        let store = MemoryStore::new();
+        for receipt in &self.receipts {
+            receipt.verify()?;
+            store.insert(receipt)?;
+        }
+        verify_ancestry(&store, &self.target)
+    }
+
+    /// Countersignatures of the target included in the bundle.
+    pub fn countersignatures(&self) -> Result<Vec<Receipt>> {
+        let store = MemoryStore::new();
+        for receipt in &self.receipts {
+            store.insert(receipt)?;
+        }
+        countersignatures(&store, &self.target)
+    }
+
+    /// Encode as compact JSON.
+    pub fn to_json(&self) -> String {
+        let receipts = self.receipts.iter().map(Receipt::to_json_value).collect();
+
+        let mut map = Map::new();
+        map.insert("receipts".into(), Value::Array(receipts));
+        map.insert("target".into(), Value::String(self.target.to_hex()));
+        Value::Object(map).to_string()
+    }
+
+    /// Decode from JSON and [`verify`](Self::verify).
+    pub fn from_json(s: &str) -> Result<Self> {
+        let value: Value =
+            serde_json::from_str(s).map_err(|e| Error::DecodingError(e.to_string()))?;
+        let map = match &value {
+            Value::Object(m) if m.len() == 2 => m,
+            _ => {
+                return Err(Error::MalformedReceipt(
+                    "provenance bundle must be an object with exactly receipts and target".into(),
+                ))
+            }
+        };
+
+        let target = match map.get("target") {
+            Some(Value::String(s)) => ReceiptId::from_hex(s)?,
+            _ => {
+                return Err(Error::MalformedReceipt(
+                    "target must be a hex string".into(),
+                ))
+            }
+        };
+        let receipts = match map.get("receipts") {
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| Receipt::from_json(&item.to_string()))
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(Error::MalformedReceipt("receipts must be an array".into())),
+        };
+
+        let bundle = Self { target, receipts };
+        bundle.verify()?;
+        Ok(bundle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conventions::countersign;
+    use crate::crypto::Keypair;
+
+    /// genesis <- claim, witnessed by org; plus an unrelated receipt.
+    fn sample(store: &MemoryStore) -> (Receipt, Receipt, Receipt) {
+        let alice = Keypair::generate();
+        let org = Keypair::generate();
+
+        let genesis = Receipt::new(&alice, "test/v1", vec![], b"genesis".to_vec()).unwrap();
+        let claim = Receipt::new(&alice, "test/v1", vec![genesis.id()], b"claim".to_vec()).unwrap();
+        store.insert(&genesis).unwrap();
+        store.insert(&claim).unwrap();
+
+        let witness = countersign(store, &org, &claim.id(), vec![]).unwrap();
+        store.insert(&witness).unwrap();
+
+        let unrelated = Receipt::new(&alice, "test/v1", vec![], b"other".to_vec()).unwrap();
+        store.insert(&unrelated).unwrap();
+
+        (genesis, claim, witness)
+    }
+
+    #[test]
+    fn test_bundle_contents() {
+        let store = MemoryStore::new();
+        let (genesis, claim, witness) = sample(&store);
+
+        let bundle = provenance(&store, &claim.id()).unwrap();
+        assert_eq!(bundle.target, claim.id());
+        assert_eq!(bundle.receipts, vec![genesis, claim, witness.clone()]);
+        assert_eq!(bundle.countersignatures().unwrap(), vec![witness]);
+        assert!(bundle.verify().is_ok());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let store = MemoryStore::new();
+        let (_, claim, _) = sample(&store);
+
+        let bundle = provenance(&store, &claim.id()).unwrap();
+        let json = bundle.to_json();
+        assert!(json.starts_with("{\"receipts\":["));
+        assert_eq!(ProvenanceBundle::from_json(&json).unwrap(), bundle);
+    }
+
+    #[test]
+    fn test_incomplete_bundle_rejected() {
+        let store = MemoryStore::new();
+        let (genesis, claim, _) = sample(&store);
+
+        let mut bundle = provenance(&store, &claim.id()).unwrap();
+        bundle.receipts.retain(|r| r.id() != genesis.id());
+        assert!(matches!(
+            bundle.verify(),
+            Err(Error::RefNotFound(id)) if id == genesis.id()
+        ));
+        assert!(ProvenanceBundle::from_json(&bundle.to_json()).is_err());
+    }
+
+    #[test]
+    fn test_requires_complete_history() {
+        let store = MemoryStore::new();
+        let keypair = Keypair::generate();
+        let missing = ReceiptId::from_bytes([0xaa; 32]);
+        let receipt = Receipt::new(&keypair, "test/v1", vec![missing], vec![]).unwrap();
+        store.insert(&receipt).unwrap();
+
+        assert!(matches!(
+            provenance(&store, &receipt.id()),
+            Err(Error::RefNotFound(id)) if id == missing
+        ));
+    }
+}
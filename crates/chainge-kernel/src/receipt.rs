@@ -8,11 +8,43 @@
 
 use std::fmt;
 
-use crate::canonical::{canonical_content, canonical_receipt, decode_receipt, sign_message, ID_DOMAIN};
+use crate::canonical::{
+    canonical_content_into, canonical_receipt, canonical_receipt_into, decode_receipt, encoded_len,
+    ID_DOMAIN, SIGN_DOMAIN,
+};
 use crate::crypto::{Author, Keypair, Sha256Hash, Signature};
 use crate::error::{Error, Result};
+use crate::json::{decode_json, encode_json, encode_json_value};
 use crate::{MAX_PAYLOAD_LEN, MAX_REFS, MAX_SCHEMA_LEN};
 
+/// `SIGN_DOMAIN || canonical_content`, built in one buffer.
+///
+/// Same bytes as `sign_message(&canonical_content(..))` without the copy.
+fn signing_message(author: &Author, schema: &str, refs: &[ReceiptId], payload: &[u8]) -> Vec<u8> {
+    let len = encoded_len(schema, refs, payload, None);
+    let mut msg = Vec::with_capacity(SIGN_DOMAIN.len() + len);
+    msg.extend_from_slice(SIGN_DOMAIN);
+    canonical_content_into(&mut msg, author, schema, refs, payload);
+    msg
+}
+
+/// Check the SPEC §5 field limits shared by construction and every decoder.
+fn validate_fields(schema: &str, refs: &[ReceiptId], payload: &[u8]) -> Result<()> {
+    if schema.len() > MAX_SCHEMA_LEN {
+        return Err(Error::SchemaTooLong(schema.len()));
+    }
+    if !schema.is_ascii() {
+        return Err(Error::SchemaNotAscii);
+    }
+    if refs.len() > MAX_REFS {
+        return Err(Error::TooManyRefs(refs.len()));
+    }
+    if payload.len() > MAX_PAYLOAD_LEN {
+        return Err(Error::PayloadTooLarge(payload.len()));
+    }
+    Ok(())
+}
+
 /// Normalize refs: sort and check for duplicates.
 /// Returns sorted refs, or error if duplicates found.
 fn normalize_refs(mut refs: Vec<ReceiptId>) -> Result<Vec<ReceiptId>> {
@@ -137,30 +169,14 @@ impl Receipt {
     ) -> Result<Self> {
         let schema = schema.into();
 
-        // Validate schema
-        if schema.len() > MAX_SCHEMA_LEN {
-            return Err(Error::SchemaTooLong(schema.len()));
-        }
-        if !schema.is_ascii() {
-            return Err(Error::SchemaNotAscii);
-        }
-
-        // Validate refs count
-        if refs.len() > MAX_REFS {
-            return Err(Error::TooManyRefs(refs.len()));
-        }
+        // Validate schema, refs count and payload size
+        validate_fields(&schema, &refs, &payload)?;
 
         // Normalize refs: sort and reject duplicates
         let refs = normalize_refs(refs)?;
 
-        // Validate payload
-        if payload.len() > MAX_PAYLOAD_LEN {
-            return Err(Error::PayloadTooLarge(payload.len()));
-        }
-
         let author = keypair.author();
-        let content = canonical_content(&author, &schema, &refs, &payload);
-        let sign_msg = sign_message(&content);
+        let sign_msg = signing_message(&author, &schema, &refs, &payload);
         let signature = keypair.sign(&sign_msg);
 
         Ok(Self {
@@ -176,18 +192,11 @@ impl Receipt {
     ///
     /// `receipt_id = sha256("chainge/receipt-id/v1" || receipt_bytes)`
     pub fn id(&self) -> ReceiptId {
-        let receipt_bytes = canonical_receipt(
-            &self.author,
-            &self.schema,
-            &self.refs,
-            &self.payload,
-            &self.signature,
-        );
-
-        // Hash with domain separation
-        let mut to_hash = Vec::with_capacity(ID_DOMAIN.len() + receipt_bytes.len());
+        // Hash with domain separation; encode straight after the prefix
+        let len = encoded_len(&self.schema, &self.refs, &self.payload, Some(&self.signature));
+        let mut to_hash = Vec::with_capacity(ID_DOMAIN.len() + len);
         to_hash.extend_from_slice(ID_DOMAIN);
-        to_hash.extend_from_slice(&receipt_bytes);
+        self.to_bytes_into(&mut to_hash);
 
         ReceiptId(Sha256Hash::hash(&to_hash).0)
     }
@@ -213,8 +222,7 @@ impl Receipt {
 
     /// Verify the signature with domain separation.
     pub fn verify(&self) -> Result<()> {
-        let content = canonical_content(&self.author, &self.schema, &self.refs, &self.payload);
-        let sign_msg = sign_message(&content);
+        let sign_msg = signing_message(&self.author, &self.schema, &self.refs, &self.payload);
         self.author.verify(&sign_msg, &self.signature)
     }
 
@@ -229,16 +237,72 @@ impl Receipt {
         )
     }
 
+    /// Append canonical CBOR bytes to `buf` (see [`to_bytes`](Self::to_bytes)).
+    ///
+    /// Lets bulk encoders reuse one buffer instead of allocating per receipt.
+    pub fn to_bytes_into(&self, buf: &mut Vec<u8>) {
+        canonical_receipt_into(
+            buf,
+            &self.author,
+            &self.schema,
+            &self.refs,
+            &self.payload,
+            &self.signature,
+        );
+    }
+
     /// Decode from canonical CBOR bytes.
     ///
-    /// Validates signature and refs ordering.
+    /// Validates field limits, refs ordering and the signature.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
         let (author, schema, refs, payload, signature) = decode_receipt(bytes)?;
+        Self::from_decoded(author, schema, refs, payload, signature)
+    }
 
-        // Validate schema is ASCII
-        if !schema.is_ascii() {
-            return Err(Error::SchemaNotAscii);
-        }
+    /// Encode to the human-readable JSON form (SPEC.md Appendix B).
+    ///
+    /// For debugging and tooling only. IDs and signatures are always computed
+    /// over the canonical CBOR bytes.
+    pub fn to_json(&self) -> String {
+        encode_json(
+            &self.author,
+            &self.schema,
+            &self.refs,
+            &self.payload,
+            &self.signature,
+        )
+    }
+
+    /// The JSON form as a [`serde_json::Value`], for embedding in larger
+    /// documents without re-parsing [`to_json`](Self::to_json).
+    pub fn to_json_value(&self) -> serde_json::Value {
+        encode_json_value(
+            &self.author,
+            &self.schema,
+            &self.refs,
+            &self.payload,
+            &self.signature,
+        )
+    }
+
+    /// Decode from the human-readable JSON form.
+    ///
+    /// Applies the same validation as [`from_bytes`](Self::from_bytes).
+    pub fn from_json(s: &str) -> Result<Self> {
+        let (author, schema, refs, payload, signature) = decode_json(s)?;
+        Self::from_decoded(author, schema, refs, payload, signature)
+    }
+
+    /// Validate decoded fields and assemble a receipt.
+    fn from_decoded(
+        author: Author,
+        schema: String,
+        refs: Vec<ReceiptId>,
+        payload: Vec<u8>,
+        signature: Signature,
+    ) -> Result<Self> {
+        // Same limits as Receipt::new; a valid signature doesn't excuse size
+        validate_fields(&schema, &refs, &payload)?;
 
         // Validate refs are sorted and unique (strict on decode - must be canonical)
         validate_refs_sorted(&refs)?;
@@ -413,6 +477,37 @@ mod tests {
         assert!(matches!(result, Err(Error::PayloadTooLarge(_))));
     }
 
+    /// Sign fields directly, bypassing the limits in `Receipt::new`.
+    fn sign_unchecked(keypair: &Keypair, schema: &str, payload: Vec<u8>) -> Receipt {
+        let author = keypair.author();
+        let signature = keypair.sign(&signing_message(&author, schema, &[], &payload));
+        Receipt {
+            author,
+            schema: schema.to_string(),
+            refs: vec![],
+            payload,
+            signature,
+        }
+    }
+
+    #[test]
+    fn test_decode_enforces_limits() {
+        let keypair = Keypair::generate();
+
+        let big = sign_unchecked(&keypair, "test/v1", vec![0u8; MAX_PAYLOAD_LEN + 1]);
+        assert!(big.verify().is_ok());
+        assert!(matches!(
+            Receipt::from_bytes(&big.to_bytes()),
+            Err(Error::PayloadTooLarge(_))
+        ));
+
+        let long = sign_unchecked(&keypair, &"x".repeat(MAX_SCHEMA_LEN + 1), vec![]);
+        assert!(matches!(
+            Receipt::from_bytes(&long.to_bytes()),
+            Err(Error::SchemaTooLong(_))
+        ));
+    }
+
     #[test]
     fn test_tampered_signature_fails() {
         let keypair = Keypair::generate();
@@ -463,4 +558,53 @@ mod tests {
         let result: std::result::Result<ciborium::value::Value, _> = ciborium::from_reader(cursor);
         assert!(result.is_ok(), "to_bytes() must produce valid CBOR");
     }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let original = Receipt::new(
+            &keypair,
+            "test/v1",
+            vec![ReceiptId::from_bytes([0xab; 32])],
+            b"hello world".to_vec(),
+        )
+        .unwrap();
+
+        let json = original.to_json();
+        let decoded = Receipt::from_json(&json).unwrap();
+
+        assert_eq!(original, decoded);
+        assert_eq!(original.id(), decoded.id());
+    }
+
+    #[test]
+    fn test_json_decode_enforces_limits() {
+        let keypair = Keypair::generate();
+
+        let big = sign_unchecked(&keypair, "test/v1", vec![0u8; MAX_PAYLOAD_LEN + 1]);
+        assert!(matches!(
+            Receipt::from_json(&big.to_json()),
+            Err(Error::PayloadTooLarge(_))
+        ));
+
+        let long = sign_unchecked(&keypair, &"x".repeat(MAX_SCHEMA_LEN + 1), vec![]);
+        assert!(matches!(
+            Receipt::from_json(&long.to_json()),
+            Err(Error::SchemaTooLong(_))
+        ));
+    }
+
+    #[test]
+    fn test_json_tampered_payload_fails() {
+        let keypair = Keypair::from_seed(&[0x42; 32]);
+        let receipt = Receipt::new(&keypair, "test/v1", vec![], b"hello".to_vec()).unwrap();
+
+        // "hello" -> "hellp"
+        let json = receipt.to_json().replace("68656c6c6f", "68656c6c70");
+
+        assert!(matches!(
+            Receipt::from_json(&json),
+            Err(Error::InvalidSignature)
+        ));
+    }
 }
@@ -1,10 +1,11 @@
 //! Store trait: the minimal interface for receipt persistence.
 
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::sync::RwLock;
 
 use crate::crypto::Author;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::receipt::{Receipt, ReceiptId};
 
 /// Result of inserting a receipt.
@@ -47,15 +48,24 @@ pub trait Store: Send + Sync {
 }
 
 /// In-memory store for testing and simple use cases.
+///
+/// Keeps an author index so [`Store::by_author`] doesn't scan every receipt;
+/// it returns an author's receipts in insertion order.
 pub struct MemoryStore {
-    receipts: RwLock<HashMap<ReceiptId, Receipt>>,
+    inner: RwLock<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    receipts: HashMap<ReceiptId, Receipt>,
+    by_author: HashMap<Author, Vec<ReceiptId>>,
 }
 
 impl MemoryStore {
     /// Create a new empty store.
     pub fn new() -> Self {
         Self {
-            receipts: RwLock::new(HashMap::new()),
+            inner: RwLock::new(Inner::default()),
         }
     }
 }
@@ -69,38 +79,42 @@ impl Default for MemoryStore {
 impl Store for MemoryStore {
     fn insert(&self, receipt: &Receipt) -> Result<InsertResult> {
         let id = receipt.id();
-        let mut receipts = self.receipts.write().unwrap();
-
-        if receipts.contains_key(&id) {
-            Ok(InsertResult::AlreadyExists)
-        } else {
-            receipts.insert(id, receipt.clone());
-            Ok(InsertResult::Inserted)
+        let mut inner = self.inner.write().unwrap();
+
+        match inner.receipts.entry(id) {
+            Entry::Occupied(_) => Ok(InsertResult::AlreadyExists),
+            Entry::Vacant(slot) => {
+                slot.insert(receipt.clone());
+                inner.by_author.entry(receipt.author).or_default().push(id);
+                Ok(InsertResult::Inserted)
+            }
         }
     }
 
     fn get(&self, id: &ReceiptId) -> Result<Option<Receipt>> {
-        let receipts = self.receipts.read().unwrap();
-        Ok(receipts.get(id).cloned())
+        let inner = self.inner.read().unwrap();
+        Ok(inner.receipts.get(id).cloned())
     }
 
     fn has(&self, id: &ReceiptId) -> Result<bool> {
-        let receipts = self.receipts.read().unwrap();
-        Ok(receipts.contains_key(id))
+        let inner = self.inner.read().unwrap();
+        Ok(inner.receipts.contains_key(id))
     }
 
     fn by_author(&self, author: &Author) -> Result<Vec<Receipt>> {
-        let receipts = self.receipts.read().unwrap();
-        Ok(receipts
-            .values()
-            .filter(|r| &r.author == author)
-            .cloned()
-            .collect())
+        let inner = self.inner.read().unwrap();
+        let ids = inner
+            .by_author
+            .get(author)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        Ok(ids.iter().map(|id| inner.receipts[id].clone()).collect())
     }
 
     fn refs_to(&self, id: &ReceiptId) -> Result<Vec<Receipt>> {
-        let receipts = self.receipts.read().unwrap();
-        Ok(receipts
+        let inner = self.inner.read().unwrap();
+        Ok(inner
+            .receipts
             .values()
             .filter(|r| r.references(id))
             .cloned()
@@ -108,13 +122,13 @@ impl Store for MemoryStore {
     }
 
     fn all_ids(&self) -> Result<Vec<ReceiptId>> {
-        let receipts = self.receipts.read().unwrap();
-        Ok(receipts.keys().cloned().collect())
+        let inner = self.inner.read().unwrap();
+        Ok(inner.receipts.keys().cloned().collect())
     }
 
     fn count(&self) -> Result<usize> {
-        let receipts = self.receipts.read().unwrap();
-        Ok(receipts.len())
+        let inner = self.inner.read().unwrap();
+        Ok(inner.receipts.len())
     }
 }
 
@@ -160,6 +174,59 @@ pub struct SyncReport {
     pub sent_2_to_1: usize,
 }
 
+/// Copy every receipt from `from` into `to`, validating each on the way.
+///
+/// Use this to move between [`Store`] implementations. A receipt is
+/// rejected, not copied, if it fails [`Receipt::verify`], if its ID doesn't
+/// match the ID it was stored under, or if `from` lists an ID it can't
+/// return. Storage errors from `to` abort the migration.
+pub fn migrate<S1: Store + ?Sized, S2: Store + ?Sized>(
+    from: &S1,
+    to: &S2,
+) -> Result<MigrationReport> {
+    let mut report = MigrationReport::default();
+
+    for id in from.all_ids()? {
+        let Some(receipt) = from.get(&id)? else {
+            report.rejected.push((
+                id,
+                Error::StorageError("listed by all_ids but not returned by get".into()),
+            ));
+            continue;
+        };
+        if receipt.id() != id {
+            let actual = receipt.id().to_hex();
+            report.rejected.push((
+                id,
+                Error::MalformedReceipt(format!("stored under wrong id (hashes to {actual})")),
+            ));
+            continue;
+        }
+        if let Err(e) = receipt.verify() {
+            report.rejected.push((id, e));
+            continue;
+        }
+
+        match to.insert(&receipt)? {
+            InsertResult::Inserted => report.copied += 1,
+            InsertResult::AlreadyExists => report.already_present += 1,
+        }
+    }
+
+    Ok(report)
+}
+
+/// Report from a migration.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    /// Receipts inserted into the destination.
+    pub copied: usize,
+    /// Receipts the destination already had.
+    pub already_present: usize,
+    /// Receipts not copied, with the reason.
+    pub rejected: Vec<(ReceiptId, Error)>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,6 +267,27 @@ mod tests {
         assert_eq!(kp2_receipts.len(), 1);
     }
 
+    #[test]
+    fn test_by_author_insertion_order() {
+        let store = MemoryStore::new();
+        let keypair = Keypair::generate();
+
+        let receipts: Vec<Receipt> = (0u8..5)
+            .map(|i| Receipt::new(&keypair, "test/v1", vec![], vec![i]).unwrap())
+            .collect();
+        for r in &receipts {
+            store.insert(r).unwrap();
+        }
+        // Duplicate insert must not duplicate the index entry
+        store.insert(&receipts[0]).unwrap();
+
+        assert_eq!(store.by_author(&keypair.author()).unwrap(), receipts);
+        assert!(store
+            .by_author(&Keypair::generate().author())
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_refs_to() {
         let store = MemoryStore::new();
@@ -221,6 +309,34 @@ mod tests {
         assert_eq!(refs.len(), 2);
     }
 
+    #[test]
+    fn test_migrate() {
+        let from = MemoryStore::new();
+        let to = MemoryStore::new();
+        let keypair = Keypair::generate();
+
+        let r1 = Receipt::new(&keypair, "test/v1", vec![], b"one".to_vec()).unwrap();
+        let r2 = Receipt::new(&keypair, "test/v1", vec![r1.id()], b"two".to_vec()).unwrap();
+        from.insert(&r1).unwrap();
+        from.insert(&r2).unwrap();
+        to.insert(&r1).unwrap();
+
+        // MemoryStore doesn't verify on insert, so a forged receipt can sit in it
+        let mut forged = Receipt::new(&keypair, "test/v1", vec![], b"three".to_vec()).unwrap();
+        forged.payload = b"tampered".to_vec();
+        from.insert(&forged).unwrap();
+
+        let report = migrate(&from, &to).unwrap();
+        assert_eq!(report.copied, 1);
+        assert_eq!(report.already_present, 1);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].0, forged.id());
+        assert!(matches!(report.rejected[0].1, Error::InvalidSignature));
+
+        assert!(to.has(&r2.id()).unwrap());
+        assert!(!to.has(&forged.id()).unwrap());
+    }
+
     #[test]
     fn test_sync() {
         let store1 = MemoryStore::new();
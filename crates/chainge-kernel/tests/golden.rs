@@ -268,6 +268,34 @@ fn test_vectors_verify() {
             v.name
         );
         assert_eq!(receipt.cid(), v.cid, "cid mismatch for {}", v.name);
+        assert_eq!(
+            chainge_kernel::Sha256Hash::from_cid(&v.cid).unwrap(),
+            chainge_kernel::Sha256Hash::hash(&receipt.to_bytes()),
+            "cid digest mismatch for {}",
+            v.name
+        );
+    }
+}
+
+#[test]
+fn test_vectors_json_roundtrip() {
+    // JSON form must carry the same hex fields and decode to the same receipt
+    let vectors = generate_all_vectors();
+
+    for v in &vectors {
+        let bytes = hex::decode(&v.receipt_bytes).unwrap();
+        let receipt = Receipt::from_bytes(&bytes).unwrap();
+
+        let json = receipt.to_json();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["author"], v.author_pk.as_str(), "author mismatch for {}", v.name);
+        assert_eq!(value["schema"], v.schema.as_str(), "schema mismatch for {}", v.name);
+        assert_eq!(value["refs"], serde_json::json!(v.refs), "refs mismatch for {}", v.name);
+        assert_eq!(value["payload"], v.payload.as_str(), "payload mismatch for {}", v.name);
+        assert_eq!(value["signature"], v.signature.as_str(), "signature mismatch for {}", v.name);
+
+        let decoded = Receipt::from_json(&json).unwrap();
+        assert_eq!(decoded.id().to_hex(), v.receipt_id, "receipt_id mismatch for {}", v.name);
     }
 }
 
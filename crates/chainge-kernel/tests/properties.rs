@@ -0,0 +1,63 @@
+//! Property tests for canonical encoding.
+//!
+//! Complements the golden vectors with generated receipts across the whole
+//! valid input space (schema length, refs count, payload size).
+
+use chainge_kernel::{Keypair, Receipt, ReceiptId, MAX_REFS, MAX_SCHEMA_LEN};
+use proptest::collection::{btree_set, vec};
+use proptest::prelude::*;
+
+/// Arbitrary valid receipt: printable ASCII schema, unique refs, any payload.
+fn arb_receipt() -> impl Strategy<Value = Receipt> {
+    (
+        any::<[u8; 32]>(),
+        proptest::string::string_regex(&format!("[ -~]{{0,{MAX_SCHEMA_LEN}}}")).unwrap(),
+        btree_set(any::<[u8; 32]>(), 0..=MAX_REFS),
+        vec(any::<u8>(), 0..2048),
+    )
+        .prop_map(|(seed, schema, refs, payload)| {
+            let refs = refs.into_iter().map(ReceiptId::from_bytes).collect();
+            Receipt::new(&Keypair::from_seed(&seed), schema, refs, payload).unwrap()
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn encode_decode_encode_is_identity(receipt in arb_receipt()) {
+        let bytes = receipt.to_bytes();
+        let decoded = Receipt::from_bytes(&bytes).unwrap();
+
+        prop_assert_eq!(decoded.to_bytes(), bytes);
+        prop_assert_eq!(decoded.id(), receipt.id());
+    }
+
+    #[test]
+    fn json_roundtrip_is_identity(receipt in arb_receipt()) {
+        let decoded = Receipt::from_json(&receipt.to_json()).unwrap();
+
+        prop_assert_eq!(decoded, receipt);
+    }
+
+    #[test]
+    fn decode_rejects_any_mutated_byte(
+        receipt in arb_receipt(),
+        index in any::<prop::sample::Index>(),
+        flip in 1u8..=255,
+    ) {
+        let mut bytes = receipt.to_bytes();
+        let i = index.index(bytes.len());
+        bytes[i] ^= flip;
+
+        prop_assert!(Receipt::from_bytes(&bytes).is_err(), "mutation at byte {} accepted", i);
+    }
+
+    #[test]
+    fn decode_rejects_truncation(receipt in arb_receipt(), index in any::<prop::sample::Index>()) {
+        let bytes = receipt.to_bytes();
+        let len = index.index(bytes.len());
+
+        prop_assert!(Receipt::from_bytes(&bytes[..len]).is_err());
+    }
+}
@@ -0,0 +1,630 @@
+//! # Chainge Keystore
+//!
+//! Passphrase-encrypted storage for one or more [`Keypair`]s in a single file.
+//!
+//! ## File Format (version 1)
+//!
+//! A JSON document. Labels and authors are readable without the passphrase;
+//! seeds are not.
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "kdf":   { "algorithm": "argon2id", "m_cost", "t_cost", "p_cost", "salt" },
+//!   "check": { "nonce", "ciphertext" },
+//!   "keys":  [ { "label", "author", "nonce", "ciphertext" }, ... ]
+//! }
+//! ```
+//!
+//! - `key = argon2id(passphrase, salt)`, 32 bytes; costs above
+//!   [`KdfParams::MAX`] are refused
+//! - Each seed is sealed with ChaCha20-Poly1305 under `key` and a random
+//!   nonce, with associated data `DOMAIN || author || label`, so entries
+//!   can't be swapped or relabeled undetected
+//! - `check` seals an empty message, so a wrong passphrase is reported on
+//!   open even when the keystore holds no keys
+//! - Binary fields are lowercase hex
+//!
+//! `create` never replaces an existing file. Every later change rewrites the
+//! file atomically (write a fresh temp file, fsync, rename, fsync the
+//! directory). On Unix the file is created with mode `0600`.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use chainge_kernel::Keypair;
+//! use chainge_keystore::Keystore;
+//!
+//! let mut keystore = Keystore::create("alice.keystore", "correct horse")?;
+//! keystore.insert("main", &Keypair::generate())?;
+//!
+//! let keystore = Keystore::open("alice.keystore", "correct horse")?;
+//! let keypair = keystore.get("main")?;
+//! # Ok::<(), chainge_keystore::Error>(())
+//! ```
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, KeyInit, Nonce};
+use chainge_kernel::{Author, Keypair};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Current file format version.
+pub const VERSION: u32 = 1;
+
+/// Domain prefix for AEAD associated data.
+pub const DOMAIN: &[u8] = b"chainge/keystore/v1";
+
+const KDF_ALGORITHM: &str = "argon2id";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Keystore error type.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Reading or writing the keystore file failed.
+    #[error("io error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The file is not a valid keystore.
+    #[error("malformed keystore: {0}")]
+    Malformed(String),
+
+    /// The file was written by an unknown format version.
+    #[error("unsupported keystore version: {0} (expected {VERSION})")]
+    UnsupportedVersion(u32),
+
+    /// The passphrase does not decrypt this keystore.
+    #[error("wrong passphrase")]
+    WrongPassphrase,
+
+    /// A key with this label is already stored.
+    #[error("label already in use: {0}")]
+    LabelExists(String),
+
+    /// No key with this label is stored.
+    #[error("no key with label: {0}")]
+    LabelNotFound(String),
+}
+
+/// Result type for keystore operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Argon2id cost parameters, recorded in the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub m_cost: u32,
+    /// Number of passes.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl KdfParams {
+    /// Largest costs accepted when creating or opening a keystore: 256 MiB,
+    /// 16 passes, 16 lanes. Keeps a crafted file from exhausting memory.
+    pub const MAX: Self = Self {
+        m_cost: 256 * 1024,
+        t_cost: 16,
+        p_cost: 16,
+    };
+
+    fn check(self) -> Result<()> {
+        let max = Self::MAX;
+        if self.m_cost > max.m_cost || self.t_cost > max.t_cost || self.p_cost > max.p_cost {
+            return Err(Error::Malformed(format!(
+                "kdf params {self:?} exceed the maximum {max:?}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for KdfParams {
+    /// OWASP's minimum recommendation for Argon2id: 19 MiB, 2 passes.
+    fn default() -> Self {
+        Self {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+/// An open keystore: the file contents plus the derived encryption key.
+pub struct Keystore {
+    path: PathBuf,
+    key: Zeroizing<[u8; 32]>,
+    file: KeystoreFile,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    kdf: KdfSection,
+    check: Sealed,
+    keys: Vec<KeyEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfSection {
+    algorithm: String,
+    #[serde(flatten)]
+    params: KdfParams,
+    salt: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeyEntry {
+    label: String,
+    author: String,
+    #[serde(flatten)]
+    sealed: Sealed,
+}
+
+impl Keystore {
+    /// Create a new, empty keystore at `path` with default KDF parameters.
+    ///
+    /// Fails if `path` already exists.
+    pub fn create(path: impl AsRef<Path>, passphrase: &str) -> Result<Self> {
+        Self::create_with_params(path, passphrase, KdfParams::default())
+    }
+
+    /// Create a new, empty keystore with explicit KDF parameters.
+    pub fn create_with_params(
+        path: impl AsRef<Path>,
+        passphrase: &str,
+        params: KdfParams,
+    ) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        // Early out before the slow KDF; create_new in write_new is what
+        // actually guarantees an existing keystore isn't replaced
+        if path.exists() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", path.display()),
+            )));
+        }
+
+        params.check()?;
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = derive_key(passphrase, &salt, params)?;
+
+        let file = KeystoreFile {
+            version: VERSION,
+            kdf: KdfSection {
+                algorithm: KDF_ALGORITHM.into(),
+                params,
+                salt: hex::encode(salt),
+            },
+            check: seal(&key, &[], &check_aad()),
+            keys: Vec::new(),
+        };
+
+        let keystore = Self { path, key, file };
+        write_new(&keystore.path, &keystore.to_json())?;
+        sync_parent(&keystore.path)?;
+        Ok(keystore)
+    }
+
+    /// Open an existing keystore.
+    ///
+    /// Returns [`Error::WrongPassphrase`] if `passphrase` doesn't match.
+    pub fn open(path: impl AsRef<Path>, passphrase: &str) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file: KeystoreFile = serde_json::from_slice(&fs::read(&path)?)
+            .map_err(|e| Error::Malformed(e.to_string()))?;
+
+        if file.version != VERSION {
+            return Err(Error::UnsupportedVersion(file.version));
+        }
+        if file.kdf.algorithm != KDF_ALGORITHM {
+            return Err(Error::Malformed(format!(
+                "unsupported kdf: {}",
+                file.kdf.algorithm
+            )));
+        }
+
+        file.kdf.params.check()?;
+        let salt = decode_hex(&file.kdf.salt, "salt")?;
+        let key = derive_key(passphrase, &salt, file.kdf.params)?;
+        open_sealed(&key, &file.check, &check_aad())?;
+
+        Ok(Self { path, key, file })
+    }
+
+    /// Path of the keystore file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Labels and authors of the stored keys, in insertion order.
+    pub fn list(&self) -> Result<Vec<(String, Author)>> {
+        self.file
+            .keys
+            .iter()
+            .map(|entry| Ok((entry.label.clone(), entry_author(entry)?)))
+            .collect()
+    }
+
+    /// Decrypt the keypair stored under `label`.
+    pub fn get(&self, label: &str) -> Result<Keypair> {
+        let entry = self
+            .file
+            .keys
+            .iter()
+            .find(|entry| entry.label == label)
+            .ok_or_else(|| Error::LabelNotFound(label.into()))?;
+        let author = entry_author(entry)?;
+
+        let seed = open_sealed(&self.key, &entry.sealed, &key_aad(&author, label))?;
+        let seed: &[u8; 32] = seed
+            .as_slice()
+            .try_into()
+            .map_err(|_| Error::Malformed(format!("{label}: seed must be 32 bytes")))?;
+
+        let keypair = Keypair::from_seed(seed);
+        if keypair.author() != author {
+            return Err(Error::Malformed(format!(
+                "{label}: seed does not match author"
+            )));
+        }
+        Ok(keypair)
+    }
+
+    /// Store `keypair` under `label` and save the file.
+    pub fn insert(&mut self, label: &str, keypair: &Keypair) -> Result<()> {
+        if self.file.keys.iter().any(|entry| entry.label == label) {
+            return Err(Error::LabelExists(label.into()));
+        }
+
+        let author = keypair.author();
+        let sealed = seal(&self.key, &*keypair.to_seed(), &key_aad(&author, label));
+        self.file.keys.push(KeyEntry {
+            label: label.into(),
+            author: author.to_hex(),
+            sealed,
+        });
+        // Keep memory in step with the file if the write fails
+        if let Err(e) = self.save() {
+            self.file.keys.pop();
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove the key stored under `label` and save the file.
+    ///
+    /// Returns false if there was no such key.
+    pub fn remove(&mut self, label: &str) -> Result<bool> {
+        let Some(index) = self.file.keys.iter().position(|entry| entry.label == label) else {
+            return Ok(false);
+        };
+        let entry = self.file.keys.remove(index);
+        if let Err(e) = self.save() {
+            self.file.keys.insert(index, entry);
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec_pretty(&self.file).expect("keystore serializes")
+    }
+
+    /// Atomically replace the file: write a sibling temp file, fsync, rename.
+    fn save(&self) -> Result<()> {
+        let mut tmp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp = self.path.with_file_name(tmp_name);
+
+        // A leftover temp file would keep its old mode; start fresh
+        match fs::remove_file(&tmp) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+        write_new(&tmp, &self.to_json())?;
+        fs::rename(&tmp, &self.path)?;
+        sync_parent(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Create `path` (failing if it exists) with mode `0600`, write and fsync.
+/// A partially written file is removed.
+fn write_new(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut out = options.open(path)?;
+    let result = out.write_all(bytes).and_then(|()| out.sync_all());
+    if result.is_err() {
+        drop(out);
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+/// Fsync the directory holding `path` so a create or rename is durable.
+fn sync_parent(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        let parent = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        fs::File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<Zeroizing<[u8; 32]>> {
+    let params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+        .map_err(|e| Error::Malformed(format!("kdf params: {e}")))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| Error::Malformed(format!("kdf: {e}")))?;
+    Ok(key)
+}
+
+fn check_aad() -> Vec<u8> {
+    [DOMAIN, b"check"].concat()
+}
+
+fn key_aad(author: &Author, label: &str) -> Vec<u8> {
+    [DOMAIN, author.as_bytes(), label.as_bytes()].concat()
+}
+
+fn seal(key: &[u8; 32], plaintext: &[u8], aad: &[u8]) -> Sealed {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .expect("encryption cannot fail for in-memory buffers");
+
+    Sealed {
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    }
+}
+
+/// Decrypt a sealed value. Authentication failure means the passphrase is
+/// wrong or the entry was tampered with; the two are indistinguishable.
+fn open_sealed(key: &[u8; 32], sealed: &Sealed, aad: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+    let nonce = decode_hex(&sealed.nonce, "nonce")?;
+    if nonce.len() != NONCE_LEN {
+        return Err(Error::Malformed(format!("nonce must be {NONCE_LEN} bytes")));
+    }
+    let ciphertext = decode_hex(&sealed.ciphertext, "ciphertext")?;
+
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad,
+            },
+        )
+        .map(Zeroizing::new)
+        .map_err(|_| Error::WrongPassphrase)
+}
+
+fn entry_author(entry: &KeyEntry) -> Result<Author> {
+    Author::from_hex(&entry.author)
+        .map_err(|e| Error::Malformed(format!("{}: author: {e}", entry.label)))
+}
+
+fn decode_hex(s: &str, field: &str) -> Result<Vec<u8>> {
+    hex::decode(s).map_err(|_| Error::Malformed(format!("{field}: invalid hex")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap parameters so tests don't spend seconds in Argon2.
+    const FAST: KdfParams = KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    fn create(dir: &tempfile::TempDir) -> Keystore {
+        Keystore::create_with_params(dir.path().join("keys"), "secret", FAST).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let alice = Keypair::generate();
+        let bob = Keypair::generate();
+
+        let mut keystore = create(&dir);
+        keystore.insert("alice", &alice).unwrap();
+        keystore.insert("bob", &bob).unwrap();
+
+        let reopened = Keystore::open(keystore.path(), "secret").unwrap();
+        assert_eq!(
+            reopened.list().unwrap(),
+            vec![
+                ("alice".into(), alice.author()),
+                ("bob".into(), bob.author())
+            ]
+        );
+        assert_eq!(*reopened.get("alice").unwrap().to_seed(), *alice.to_seed());
+        assert_eq!(reopened.get("bob").unwrap().author(), bob.author());
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = create(&dir);
+
+        // Detected even with no keys stored
+        let result = Keystore::open(keystore.path(), "wrong");
+        assert!(matches!(result, Err(Error::WrongPassphrase)));
+    }
+
+    #[test]
+    fn test_labels() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut keystore = create(&dir);
+        keystore.insert("main", &Keypair::generate()).unwrap();
+
+        let result = keystore.insert("main", &Keypair::generate());
+        assert!(matches!(result, Err(Error::LabelExists(_))));
+        assert!(matches!(
+            keystore.get("other"),
+            Err(Error::LabelNotFound(_))
+        ));
+
+        assert!(keystore.remove("main").unwrap());
+        assert!(!keystore.remove("main").unwrap());
+        let reopened = Keystore::open(keystore.path(), "secret").unwrap();
+        assert!(reopened.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_create_refuses_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = create(&dir);
+
+        let result = Keystore::create_with_params(keystore.path(), "secret", FAST);
+        assert!(matches!(result, Err(Error::Io(e)) if e.kind() == io::ErrorKind::AlreadyExists));
+
+        // Past the early check, the final write still refuses to replace it
+        let err = write_new(keystore.path(), b"{}").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert!(Keystore::open(keystore.path(), "secret").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stale_temp_file_mode_not_inherited() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut keystore = create(&dir);
+        let tmp = dir.path().join("keys.tmp");
+        fs::write(&tmp, b"stale").unwrap();
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o644)).unwrap();
+
+        keystore.insert("main", &Keypair::generate()).unwrap();
+        let mode = fs::metadata(keystore.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!tmp.exists());
+    }
+
+    #[test]
+    fn test_failed_save_leaves_keystore_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut keystore = create(&dir);
+        let keypair = Keypair::generate();
+        keystore.insert("kept", &Keypair::generate()).unwrap();
+
+        // A directory where the temp file goes makes every save fail
+        let blocker = dir.path().join("keys.tmp");
+        fs::create_dir(&blocker).unwrap();
+        assert!(keystore.insert("main", &keypair).is_err());
+        assert!(keystore.remove("kept").is_err());
+        assert_eq!(keystore.list().unwrap().len(), 1);
+        assert_eq!(keystore.list().unwrap()[0].0, "kept");
+
+        fs::remove_dir(&blocker).unwrap();
+        keystore.insert("main", &keypair).unwrap();
+        let reopened = Keystore::open(keystore.path(), "secret").unwrap();
+        assert_eq!(reopened.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_kdf_params_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let huge = KdfParams {
+            m_cost: KdfParams::MAX.m_cost + 1,
+            ..FAST
+        };
+        let result = Keystore::create_with_params(dir.path().join("big"), "secret", huge);
+        assert!(matches!(result, Err(Error::Malformed(_))));
+
+        // A file edited to demand more memory is refused before deriving
+        let keystore = create(&dir);
+        let text = fs::read_to_string(keystore.path()).unwrap();
+        let text = text.replace("\"m_cost\": 64", "\"m_cost\": 4194304");
+        fs::write(keystore.path(), text).unwrap();
+        assert!(matches!(
+            Keystore::open(keystore.path(), "secret"),
+            Err(Error::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_relabeled_entry_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut keystore = create(&dir);
+        keystore.insert("alice", &Keypair::generate()).unwrap();
+
+        // Labels are bound into the AEAD associated data
+        let text = fs::read_to_string(keystore.path()).unwrap();
+        fs::write(keystore.path(), text.replace("\"alice\"", "\"mallory\"")).unwrap();
+
+        let reopened = Keystore::open(keystore.path(), "secret").unwrap();
+        assert!(matches!(
+            reopened.get("mallory"),
+            Err(Error::WrongPassphrase)
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = create(&dir);
+
+        let text = fs::read_to_string(keystore.path()).unwrap();
+        fs::write(
+            keystore.path(),
+            text.replace("\"version\": 1", "\"version\": 2"),
+        )
+        .unwrap();
+
+        let result = Keystore::open(keystore.path(), "secret");
+        assert!(matches!(result, Err(Error::UnsupportedVersion(2))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let keystore = create(&dir);
+        let mode = fs::metadata(keystore.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,16 @@
+//! Fuzz strict CBOR decoding of untrusted receipt bytes.
+//!
+//! Invariant: anything that decodes re-encodes to exactly the input, so its
+//! receipt ID is the hash of the bytes it came from.
+
+#![no_main]
+
+use chainge_kernel::Receipt;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(receipt) = Receipt::from_bytes(data) {
+        assert_eq!(receipt.to_bytes(), data);
+        assert!(receipt.verify().is_ok());
+    }
+});
@@ -0,0 +1,18 @@
+//! Fuzz the JSON view decoder.
+//!
+//! Invariant: anything that decodes survives a CBOR and JSON round trip.
+
+#![no_main]
+
+use chainge_kernel::Receipt;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(receipt) = Receipt::from_json(text) {
+        assert_eq!(Receipt::from_bytes(&receipt.to_bytes()).unwrap(), receipt);
+        assert_eq!(Receipt::from_json(&receipt.to_json()).unwrap(), receipt);
+    }
+});